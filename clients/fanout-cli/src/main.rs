@@ -0,0 +1,770 @@
+//! `fanout`: manage wallet fanouts from the command line, without a Node toolchain.
+
+mod manifest;
+mod reconcile;
+mod runner;
+
+use std::path::PathBuf;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, system_program};
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand};
+use reconcile::{reconcile, ReconcileArgs};
+use runner::Runner;
+use serde_json::json;
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use tuktuk_program::{
+  cron::accounts::CronJobV0,
+  tuktuk::{self, client},
+};
+use wallet_fanout_client::{
+  accounts::{
+    fetch, fetch_global_state, fetch_optional, fetch_token_inflows, fetch_wallet_share,
+    fetch_wallet_shares,
+  },
+  instructions as ix,
+  pdas::{
+    cron_job_key, fanout_key, global_state_key, queue_authority_key, task_queue_authority_key,
+    voucher_key, wallet_share_key,
+  },
+  wallet_fanout::{
+    ForfeitVoucherV0Args, InitializeFanoutV0Args, InitializeTokenInflowCronJobV0Args,
+    RequeueFanoutTaskArgsV0, UnsuspendWalletShareV0Args, UpdateFanoutV0Args,
+    UpdateTokenInflowV0Args, UpdateWalletShareV0Args,
+  },
+  ForfeitDestination, VoucherV0,
+};
+
+#[derive(Parser)]
+#[command(name = "fanout", about = "Manage wallet fanouts", version)]
+struct Cli {
+  /// RPC URL
+  #[arg(
+    short,
+    long,
+    global = true,
+    env = "RPC_URL",
+    default_value = "https://api.devnet.solana.com"
+  )]
+  url: String,
+  /// Keypair that signs and pays for transactions. Defaults to ~/.config/solana/id.json
+  #[arg(short, long, global = true)]
+  keypair: Option<PathBuf>,
+  /// Simulate transactions instead of sending them
+  #[arg(long, global = true)]
+  dry_run: bool,
+  /// Print results as JSON
+  #[arg(long, global = true)]
+  json: bool,
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Args)]
+struct FanoutArg {
+  /// Name of the fanout
+  #[arg(short, long)]
+  fanout: String,
+}
+
+#[derive(Args)]
+struct MintArg {
+  /// Mint of the inflow. Omit for SOL
+  #[arg(short, long)]
+  mint: Option<Pubkey>,
+}
+
+#[derive(Args)]
+struct PauseTarget {
+  /// Only pause or resume the inflow of this mint
+  #[arg(short, long, conflicts_with = "sol")]
+  mint: Option<Pubkey>,
+  /// Only pause or resume the SOL inflow
+  #[arg(long)]
+  sol: bool,
+}
+
+impl PauseTarget {
+  fn mint(&self) -> Option<Pubkey> {
+    if self.sol {
+      Some(Pubkey::default())
+    } else {
+      self.mint
+    }
+  }
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Add the queue authority to a task queue and initialize the global state
+  Init {
+    /// Task queue that runs fanout cron jobs. The signer must be its update authority
+    #[arg(short, long)]
+    task_queue: Pubkey,
+  },
+  /// Create a fanout and its cron job
+  CreateFanout {
+    /// Name of the fanout, which also names its cron job
+    #[arg(short, long)]
+    name: String,
+    /// Cron schedule for paying out vouchers
+    #[arg(short, long)]
+    schedule: String,
+    /// Shares wallet shares are issued out of
+    #[arg(short, long)]
+    total_shares: u32,
+  },
+  /// Create or update a wallet share
+  SetShare {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Index of the wallet share
+    #[arg(short, long)]
+    index: u32,
+    /// Wallet the share pays out to
+    #[arg(short, long)]
+    wallet: Pubkey,
+    #[arg(short, long)]
+    shares: u32,
+  },
+  /// Close a wallet share. Its vouchers can be closed afterwards
+  RemoveShare {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Index of the wallet share
+    #[arg(short, long)]
+    index: u32,
+  },
+  /// Hold back a wallet share's payouts until it is unsuspended
+  Suspend {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Index of the wallet share
+    #[arg(short, long)]
+    index: u32,
+  },
+  /// Release what a suspended wallet share was held back, or return it to the pool with `--forfeit`
+  Unsuspend {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Index of the wallet share
+    #[arg(short, long)]
+    index: u32,
+    #[arg(long)]
+    forfeit: bool,
+  },
+  /// Create a wallet share's voucher for a mint, scheduling its payouts
+  CreateVoucher {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Index of the wallet share
+    #[arg(short, long)]
+    index: u32,
+    #[command(flatten)]
+    mint: MintArg,
+  },
+  /// Stop payouts of the fanout, or of one of its inflows. Inflow keeps accruing
+  Pause {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    #[command(flatten)]
+    target: PauseTarget,
+  },
+  /// Resume payouts paused by `pause`
+  Resume {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    #[command(flatten)]
+    target: PauseTarget,
+  },
+  /// Give an inflow its own cron job, paying its new vouchers on a separate schedule
+  Schedule {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    #[command(flatten)]
+    mint: MintArg,
+    /// Cron schedule for paying out the inflow's vouchers
+    #[arg(short, long)]
+    schedule: String,
+  },
+  /// Requeue the fanout's cron job, e.g. after it fell off the task queue
+  Requeue {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Requeue the cron job of this mint's inflow instead
+    #[arg(short, long, conflicts_with = "sol")]
+    mint: Option<Pubkey>,
+    /// Requeue the cron job of the SOL inflow instead
+    #[arg(long)]
+    sol: bool,
+  },
+  /// Add, update and remove wallet shares and vouchers to match a CSV or JSON manifest of
+  /// `wallet,shares[,index]` entries
+  Reconcile {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Path to a .csv or .json manifest
+    manifest: PathBuf,
+    /// Mint every member should have a voucher for, on top of the fanout's existing inflows
+    #[arg(long = "mint")]
+    mints: Vec<Pubkey>,
+    /// Make sure every member has a SOL voucher
+    #[arg(long)]
+    sol: bool,
+    /// Most changes to send in one transaction
+    #[arg(long, default_value_t = 4)]
+    batch_size: usize,
+  },
+  /// Set the amount below which cron claims skip a voucher of an inflow and let it accrue
+  MinPayout {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    #[command(flatten)]
+    mint: MintArg,
+    /// Minimum payout in the mint's base units, 0 to pay out any amount
+    #[arg(short, long)]
+    amount: u64,
+  },
+  /// Set how long a voucher's pending amount can go undelivered before it can be forfeited
+  ClaimWindow {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Seconds in the window, 0 to remove it
+    #[arg(short, long)]
+    secs: u64,
+  },
+  /// Forfeit a voucher's balance once its claim window has elapsed, redistributing it to the other
+  /// members by default
+  Forfeit {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Index of the wallet share
+    #[arg(short, long)]
+    index: u32,
+    #[command(flatten)]
+    mint: MintArg,
+    /// Send the balance to the fanout's treasury instead
+    #[arg(long)]
+    treasury: bool,
+  },
+  /// Close the voucher of a removed wallet share
+  CloseVoucher {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    /// Index of the wallet share
+    #[arg(short, long)]
+    index: u32,
+    #[command(flatten)]
+    mint: MintArg,
+  },
+  /// Close an inflow once all its vouchers are closed
+  CloseInflow {
+    #[command(flatten)]
+    fanout: FanoutArg,
+    #[command(flatten)]
+    mint: MintArg,
+  },
+  /// Close a fanout and its cron job
+  CloseFanout {
+    #[command(flatten)]
+    fanout: FanoutArg,
+  },
+  /// Show a fanout with its wallet shares and inflows
+  Show {
+    #[command(flatten)]
+    fanout: FanoutArg,
+  },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+  let cli = Cli::parse();
+  let keypair_path = match cli.keypair {
+    Some(path) => path,
+    None => std::env::var("HOME")
+      .map(|home| PathBuf::from(home).join(".config/solana/id.json"))
+      .map_err(|_| anyhow!("HOME is not set, pass --keypair"))?,
+  };
+  let payer = solana_keypair::read_keypair_file(&keypair_path)
+    .map_err(|err| anyhow!("failed to read keypair {}: {err}", keypair_path.display()))?;
+  let runner = Runner {
+    rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+    payer,
+    dry_run: cli.dry_run,
+    json: cli.json,
+  };
+
+  run(&runner, cli.command).await
+}
+
+async fn run(runner: &Runner, command: Command) -> Result<()> {
+  let payer = runner.payer();
+  match command {
+    Command::Init { task_queue } => {
+      let queue_authority = queue_authority_key().0;
+      let task_queue_authority = task_queue_authority_key(&task_queue, &queue_authority).0;
+      let mut instructions = vec![];
+      if runner.rpc.get_balance(&task_queue_authority).await? == 0 {
+        instructions.push(add_queue_authority(&payer, &task_queue, &queue_authority));
+      }
+      instructions.push(ix::initialize_global_state_v0(&payer, &payer, &task_queue));
+      let mut result = runner.execute(&instructions).await?;
+      result["global_state"] = json!(global_state_key().0.to_string());
+      runner.report("Initialized global state", result)
+    }
+    Command::CreateFanout {
+      name,
+      schedule,
+      total_shares,
+    } => {
+      let global_state = fetch_global_state(&runner.rpc).await?;
+      let instruction = ix::initialize_fanout_v0(
+        &payer,
+        &payer,
+        &global_state.task_queue,
+        runner.next_cron_job_id().await?,
+        runner.next_task_id(&global_state.task_queue).await?,
+        InitializeFanoutV0Args {
+          name: name.clone(),
+          schedule,
+          total_shares,
+        },
+      );
+      let mut result = runner.execute(&[instruction]).await?;
+      result["fanout"] = json!(fanout_key(&name).0.to_string());
+      runner.report(&format!("Created fanout {name}"), result)
+    }
+    Command::SetShare {
+      fanout,
+      index,
+      wallet,
+      shares,
+    } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let wallet_share = wallet_share_key(&fanout, index).0;
+      let mut instructions: Vec<Instruction> = runner
+        .migrate_wallet_share(&wallet_share)
+        .await?
+        .into_iter()
+        .collect();
+      instructions.push(ix::update_wallet_share_v0(
+        &payer,
+        &payer,
+        &fanout,
+        &wallet,
+        &runner.inflows(&fanout).await?,
+        UpdateWalletShareV0Args { shares, index },
+      ));
+      let mut result = runner.execute(&instructions).await?;
+      result["wallet_share"] = json!(wallet_share.to_string());
+      runner.report(&format!("Set share {index} to {shares}"), result)
+    }
+    Command::RemoveShare { fanout, index } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let wallet_share = wallet_share_key(&fanout, index).0;
+      let wallet_share_acc = fetch_wallet_share(&runner.rpc, &wallet_share).await?;
+      let mut instructions: Vec<Instruction> = runner
+        .migrate_wallet_share(&wallet_share)
+        .await?
+        .into_iter()
+        .collect();
+      instructions.push(ix::close_wallet_share_v0(
+        &payer,
+        &fanout,
+        &wallet_share,
+        &wallet_share_acc.rent_refund,
+        &runner.inflows(&fanout).await?,
+      ));
+      let result = runner.execute(&instructions).await?;
+      runner.report(&format!("Removed share {index}"), result)
+    }
+    Command::Suspend { fanout, index } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let wallet_share = wallet_share_key(&fanout, index).0;
+      let mut instructions: Vec<Instruction> = runner
+        .migrate_wallet_share(&wallet_share)
+        .await?
+        .into_iter()
+        .collect();
+      instructions.push(ix::suspend_wallet_share_v0(&payer, &fanout, &wallet_share));
+      let result = runner.execute(&instructions).await?;
+      runner.report(&format!("Suspended share {index}"), result)
+    }
+    Command::Unsuspend {
+      fanout,
+      index,
+      forfeit,
+    } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let wallet_share = wallet_share_key(&fanout, index).0;
+      let instruction = ix::unsuspend_wallet_share_v0(
+        &payer,
+        &fanout,
+        &wallet_share,
+        &runner.inflows(&fanout).await?,
+        UnsuspendWalletShareV0Args { forfeit },
+      );
+      let result = runner.execute(&[instruction]).await?;
+      let message = if forfeit {
+        format!("Unsuspended share {index}, forfeiting its escrow")
+      } else {
+        format!("Unsuspended share {index}")
+      };
+      runner.report(&message, result)
+    }
+    Command::CreateVoucher {
+      fanout,
+      index,
+      mint,
+    } => {
+      let (fanout, fanout_acc) = runner.fanout(&fanout.fanout).await?;
+      let wallet_share = wallet_share_key(&fanout, index).0;
+      let inflow = runner.inflow(mint.mint).await?;
+      let cron_job = runner
+        .voucher_cron_job(&fanout, &fanout_acc, &inflow.mint)
+        .await?;
+      let instruction = match mint.mint {
+        Some(mint) => ix::initialize_voucher_v0(
+          &payer,
+          &fanout,
+          &fanout_acc,
+          &cron_job,
+          &wallet_share,
+          &mint,
+          &inflow.token_program,
+        ),
+        None => {
+          ix::initialize_sol_voucher_v0(&payer, &fanout, &fanout_acc, &cron_job, &wallet_share)
+        }
+      };
+      let mut result = runner.execute(&[instruction]).await?;
+      result["voucher"] = json!(voucher_key(&fanout, &inflow.mint, &wallet_share)
+        .0
+        .to_string());
+      runner.report(&format!("Created voucher for share {index}"), result)
+    }
+    Command::Pause { fanout, target } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let mint = target.mint();
+      let instruction = ix::pause_fanout_v0(&payer, &payer, &fanout, mint.as_ref());
+      let result = runner.execute(&[instruction]).await?;
+      runner.report("Paused payouts", result)
+    }
+    Command::Resume { fanout, target } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let mint = target.mint();
+      let instruction = ix::resume_fanout_v0(&payer, &payer, &fanout, mint.as_ref());
+      let result = runner.execute(&[instruction]).await?;
+      runner.report("Resumed payouts", result)
+    }
+    Command::Schedule {
+      fanout,
+      mint,
+      schedule,
+    } => {
+      let (fanout, fanout_acc) = runner.fanout(&fanout.fanout).await?;
+      let global_state = fetch_global_state(&runner.rpc).await?;
+      let cron_job_id = runner.next_cron_job_id().await?;
+      let instruction = ix::initialize_token_inflow_cron_job_v0(
+        &payer,
+        &payer,
+        &fanout,
+        &fanout_acc,
+        &mint.mint.unwrap_or_default(),
+        &global_state.task_queue,
+        cron_job_id,
+        runner.next_task_id(&global_state.task_queue).await?,
+        InitializeTokenInflowCronJobV0Args {
+          schedule: schedule.clone(),
+        },
+      );
+      let mut result = runner.execute(&[instruction]).await?;
+      result["cron_job"] = json!(cron_job_key(&queue_authority_key().0, cron_job_id)
+        .0
+        .to_string());
+      runner.report(&format!("Scheduled the inflow on {schedule}"), result)
+    }
+    Command::Requeue { fanout, mint, sol } => {
+      let (fanout, fanout_acc) = runner.fanout(&fanout.fanout).await?;
+      let mint = if sol { Some(Pubkey::default()) } else { mint };
+      let cron_job = match mint {
+        Some(mint) => runner.voucher_cron_job(&fanout, &fanout_acc, &mint).await?,
+        None => fanout_acc.cron_job,
+      };
+      let global_state = fetch_global_state(&runner.rpc).await?;
+      let task_id = runner.next_task_id(&global_state.task_queue).await?;
+      let instruction = ix::requeue_fanout_task_v0(
+        &payer,
+        &fanout,
+        &cron_job,
+        &global_state.task_queue,
+        mint.as_ref(),
+        RequeueFanoutTaskArgsV0 { task_id },
+      );
+      let mut result = runner.execute(&[instruction]).await?;
+      result["task_id"] = json!(task_id);
+      runner.report("Requeued fanout task", result)
+    }
+    Command::Reconcile {
+      fanout,
+      manifest,
+      mints,
+      sol,
+      batch_size,
+    } => {
+      reconcile(
+        runner,
+        ReconcileArgs {
+          fanout: &fanout.fanout,
+          manifest: &manifest,
+          mints,
+          sol,
+          batch_size,
+        },
+      )
+      .await
+    }
+    Command::MinPayout {
+      fanout,
+      mint,
+      amount,
+    } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let instruction = ix::update_token_inflow_v0(
+        &payer,
+        &fanout,
+        &mint.mint.unwrap_or_default(),
+        UpdateTokenInflowV0Args {
+          min_payout: Some(amount),
+        },
+      );
+      let result = runner.execute(&[instruction]).await?;
+      runner.report(&format!("Set the minimum payout to {amount}"), result)
+    }
+    Command::ClaimWindow { fanout, secs } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let instruction = ix::update_fanout_v0(
+        &payer,
+        &payer,
+        &fanout,
+        UpdateFanoutV0Args {
+          transfer_fee_policy: None,
+          claim_window_secs: Some(secs),
+        },
+      );
+      let result = runner.execute(&[instruction]).await?;
+      let message = if secs == 0 {
+        "Removed the claim window".to_string()
+      } else {
+        format!("Set the claim window to {secs}s")
+      };
+      runner.report(&message, result)
+    }
+    Command::Forfeit {
+      fanout,
+      index,
+      mint,
+      treasury,
+    } => {
+      let (fanout, _) = runner.fanout(&fanout.fanout).await?;
+      let inflow = runner.inflow(mint.mint).await?;
+      let voucher = voucher_key(&fanout, &inflow.mint, &wallet_share_key(&fanout, index).0).0;
+      let voucher_acc: VoucherV0 = fetch(&runner.rpc, &voucher).await?;
+      let destination = if treasury {
+        ForfeitDestination::Treasury
+      } else {
+        ForfeitDestination::Redistribute
+      };
+      let instruction = ix::forfeit_voucher_v0(
+        &payer,
+        &fanout,
+        &voucher,
+        &voucher_acc,
+        &inflow.token_program,
+        ForfeitVoucherV0Args { destination },
+      );
+      let result = runner.execute(&[instruction]).await?;
+      runner.report(&format!("Forfeited voucher for share {index}"), result)
+    }
+    Command::CloseVoucher {
+      fanout,
+      index,
+      mint,
+    } => {
+      let (fanout, fanout_acc) = runner.fanout(&fanout.fanout).await?;
+      let inflow = runner.inflow(mint.mint).await?;
+      let voucher = voucher_key(&fanout, &inflow.mint, &wallet_share_key(&fanout, index).0).0;
+      let voucher_acc: VoucherV0 = fetch(&runner.rpc, &voucher).await?;
+      let cron_job = runner
+        .scheduled_cron_job(&fanout, &fanout_acc, &voucher_acc)
+        .await?;
+      let instruction = match mint.mint {
+        Some(_) => ix::close_voucher_v0(
+          &payer,
+          &payer,
+          &fanout,
+          &cron_job,
+          &voucher,
+          &voucher_acc,
+          &inflow.token_program,
+        ),
+        None => {
+          ix::close_sol_voucher_v0(&payer, &payer, &fanout, &cron_job, &voucher, &voucher_acc)
+        }
+      };
+      let result = runner.execute(&[instruction]).await?;
+      runner.report(&format!("Closed voucher for share {index}"), result)
+    }
+    Command::CloseInflow { fanout, mint } => {
+      let (fanout, fanout_acc) = runner.fanout(&fanout.fanout).await?;
+      let inflow = runner.inflow(mint.mint).await?;
+      let token_inflow =
+        wallet_fanout_client::accounts::fetch_token_inflow(&runner.rpc, &fanout, &inflow.mint)
+          .await?
+          .ok_or_else(|| anyhow!("inflow does not exist"))?;
+      let instruction = match mint.mint {
+        Some(mint) => ix::close_token_inflow_v0(
+          &payer,
+          &fanout,
+          &fanout_acc,
+          token_inflow.cron_job.as_ref(),
+          &mint,
+          &token_inflow.rent_refund,
+          &inflow.token_program,
+        ),
+        None => ix::close_sol_inflow_v0(
+          &payer,
+          &fanout,
+          &fanout_acc,
+          token_inflow.cron_job.as_ref(),
+          &token_inflow.rent_refund,
+        ),
+      };
+      let result = runner.execute(&[instruction]).await?;
+      runner.report("Closed inflow", result)
+    }
+    Command::CloseFanout { fanout } => {
+      let (fanout, fanout_acc) = runner.fanout(&fanout.fanout).await?;
+      let instruction = ix::close_fanout_v0(&payer, &fanout, &fanout_acc);
+      let result = runner.execute(&[instruction]).await?;
+      runner.report(&format!("Closed fanout {}", fanout_acc.name), result)
+    }
+    Command::Show { fanout } => {
+      let (fanout, fanout_acc) = runner.fanout(&fanout.fanout).await?;
+      let wallet_shares = fetch_wallet_shares(&runner.rpc, &fanout).await?;
+      let token_inflows = fetch_token_inflows(&runner.rpc, &fanout).await?;
+      let cron_job: Option<CronJobV0> = fetch_optional(&runner.rpc, &fanout_acc.cron_job).await?;
+      let schedule = cron_job.map(|cron_job| cron_job.schedule);
+      let mut inflow_schedules = vec![];
+      for (_, token_inflow) in &token_inflows {
+        let schedule = match token_inflow.cron_job {
+          Some(cron_job) => fetch_optional::<CronJobV0>(&runner.rpc, &cron_job)
+            .await?
+            .map(|cron_job| cron_job.schedule),
+          None => None,
+        };
+        inflow_schedules.push(schedule);
+      }
+      let result = json!({
+        "fanout": fanout.to_string(),
+        "name": fanout_acc.name,
+        "authority": fanout_acc.authority.to_string(),
+        "schedule": schedule,
+        "cron_job": fanout_acc.cron_job.to_string(),
+        "total_shares": fanout_acc.total_shares,
+        "total_shares_issued": fanout_acc.total_shares_issued,
+        "paused": fanout_acc.paused,
+        "claim_window_secs": fanout_acc.claim_window_secs,
+        "wallet_shares": wallet_shares.iter().map(|(key, wallet_share)| json!({
+          "wallet_share": key.to_string(),
+          "index": wallet_share.id,
+          "wallet": wallet_share.wallet.to_string(),
+          "shares": wallet_share.shares,
+          "suspended": wallet_share.suspended,
+        })).collect::<Vec<_>>(),
+        "inflows": token_inflows.iter().zip(&inflow_schedules).map(|((key, token_inflow), schedule)| json!({
+          "token_inflow": key.to_string(),
+          "mint": (token_inflow.mint != Pubkey::default()).then(|| token_inflow.mint.to_string()),
+          "total_inflow": token_inflow.total_inflow,
+          "num_vouchers": token_inflow.num_vouchers,
+          "paused": token_inflow.paused,
+          "min_payout": token_inflow.min_payout,
+          "cron_job": token_inflow.cron_job.map(|cron_job| cron_job.to_string()),
+          "schedule": schedule,
+        })).collect::<Vec<_>>(),
+      });
+      if runner.json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+      println!(
+        "Fanout {} ({fanout}){}\n  authority: {}\n  schedule: {}\n  shares issued: {}/{}",
+        fanout_acc.name,
+        if fanout_acc.paused { " [paused]" } else { "" },
+        fanout_acc.authority,
+        schedule.as_deref().unwrap_or("<no cron job>"),
+        fanout_acc.total_shares_issued,
+        fanout_acc.total_shares
+      );
+      if let Some(claim_window_secs) = fanout_acc.claim_window_secs {
+        println!("  claim window: {claim_window_secs}s");
+      }
+      for (key, wallet_share) in &wallet_shares {
+        println!(
+          "  share {} ({key}): {} shares to {}{}",
+          wallet_share.id,
+          wallet_share.shares,
+          wallet_share.wallet,
+          if wallet_share.suspended {
+            " [suspended]"
+          } else {
+            ""
+          }
+        );
+      }
+      for ((key, token_inflow), schedule) in token_inflows.iter().zip(&inflow_schedules) {
+        println!(
+          "  inflow {key}: mint {}, {} total, {} vouchers{}",
+          token_inflow.mint,
+          token_inflow.total_inflow,
+          token_inflow.num_vouchers,
+          if token_inflow.paused { " [paused]" } else { "" }
+        );
+        if token_inflow.min_payout > 0 {
+          println!("    min payout: {}", token_inflow.min_payout);
+        }
+        if token_inflow.cron_job.is_some() {
+          println!(
+            "    schedule: {}",
+            schedule.as_deref().unwrap_or("<no cron job>")
+          );
+        }
+      }
+      Ok(())
+    }
+  }
+}
+
+/// Allows the fanout program's queue authority to queue tasks on the task queue
+fn add_queue_authority(
+  update_authority: &Pubkey,
+  task_queue: &Pubkey,
+  queue_authority: &Pubkey,
+) -> Instruction {
+  use anchor_lang::{InstructionData, ToAccountMetas};
+
+  Instruction {
+    program_id: tuktuk::ID,
+    accounts: client::accounts::AddQueueAuthorityV0 {
+      payer: *update_authority,
+      update_authority: *update_authority,
+      queue_authority: *queue_authority,
+      task_queue_authority: task_queue_authority_key(task_queue, queue_authority).0,
+      task_queue: *task_queue,
+      system_program: system_program::ID,
+    }
+    .to_account_metas(None),
+    data: client::args::AddQueueAuthorityV0 {}.data(),
+  }
+}
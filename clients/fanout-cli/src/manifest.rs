@@ -0,0 +1,50 @@
+//! Member manifests for `fanout reconcile`: a CSV with a `wallet,shares[,index]` header, or a JSON
+//! array of `{ "wallet", "shares", "index"? }` objects.
+
+use std::{path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ManifestEntry {
+  #[serde(deserialize_with = "deserialize_pubkey")]
+  pub wallet: Pubkey,
+  pub shares: u32,
+  /// Wallet share index. When omitted, the wallet's existing share is reused, or a new index is
+  /// allocated
+  #[serde(default)]
+  pub index: Option<u32>,
+}
+
+fn deserialize_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+  let key = String::deserialize(deserializer)?;
+  Pubkey::from_str(key.trim()).map_err(serde::de::Error::custom)
+}
+
+pub fn read_manifest(path: &Path) -> Result<Vec<ManifestEntry>> {
+  let contents =
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+  let entries = match path.extension().and_then(|ext| ext.to_str()) {
+    Some("csv") => csv::ReaderBuilder::new()
+      .trim(csv::Trim::All)
+      .from_reader(contents.as_bytes())
+      .deserialize()
+      .collect::<Result<Vec<ManifestEntry>, _>>()
+      .with_context(|| format!("invalid manifest {}", path.display()))?,
+    Some("json") => serde_json::from_str(&contents)
+      .with_context(|| format!("invalid manifest {}", path.display()))?,
+    _ => bail!("manifest must be a .csv or .json file"),
+  };
+
+  let mut indexes = std::collections::HashSet::new();
+  for entry in &entries {
+    if let Some(index) = entry.index {
+      if !indexes.insert(index) {
+        bail!("index {index} appears more than once in the manifest");
+      }
+    }
+  }
+  Ok(entries)
+}
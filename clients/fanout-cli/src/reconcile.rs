@@ -0,0 +1,726 @@
+//! `fanout reconcile`: diffs a manifest against a fanout's wallet shares and vouchers, then sends
+//! the changes in as few transactions as fit.
+
+use std::{
+  collections::{BTreeMap, HashMap, HashSet},
+  path::Path,
+};
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use solana_transaction::Transaction;
+use wallet_fanout_client::{
+  accounts::{
+    fetch_legacy_vouchers, fetch_legacy_wallet_shares, fetch_vouchers, fetch_wallet_shares,
+  },
+  instructions::{self as ix, next_cron_transaction_id, Inflow},
+  pdas::{voucher_key, wallet_share_key},
+  wallet_fanout::UpdateWalletShareV0Args,
+  VoucherV0, WalletShareV0,
+};
+
+use crate::{
+  manifest::{read_manifest, ManifestEntry},
+  runner::Runner,
+};
+
+/// Largest serialized transaction the network accepts
+const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Instructions that have to land in the same transaction, e.g. claiming a share's vouchers right
+/// before closing it so nothing is left pending
+struct Step {
+  action: Value,
+  instructions: Vec<Instruction>,
+}
+
+pub struct ReconcileArgs<'a> {
+  pub fanout: &'a str,
+  pub manifest: &'a Path,
+  /// Mints every member should have a voucher for, on top of the fanout's existing inflows
+  pub mints: Vec<Pubkey>,
+  pub sol: bool,
+  pub batch_size: usize,
+}
+
+pub async fn reconcile(runner: &Runner, args: ReconcileArgs<'_>) -> Result<()> {
+  let payer = runner.payer();
+  let (fanout, mut fanout_acc) = runner.fanout(args.fanout).await?;
+  let entries = read_manifest(args.manifest)?;
+
+  let wallet_shares: BTreeMap<u32, (Pubkey, WalletShareV0)> =
+    fetch_wallet_shares(&runner.rpc, &fanout)
+      .await
+      .context("failed to fetch wallet shares")?
+      .into_iter()
+      .map(|(key, wallet_share)| (wallet_share.id, (key, wallet_share)))
+      .collect();
+  // Wallet shares from before they could be suspended are grown before they are written to
+  let legacy_wallet_shares: HashSet<Pubkey> = fetch_legacy_wallet_shares(&runner.rpc, &fanout)
+    .await?
+    .into_iter()
+    .collect();
+  let migrate = |wallet_share: &Pubkey| {
+    legacy_wallet_shares
+      .contains(wallet_share)
+      .then(|| ix::migrate_wallet_share_v0(&payer, wallet_share))
+  };
+  let mut vouchers: HashMap<Pubkey, Vec<(Pubkey, VoucherV0)>> = HashMap::new();
+  for (key, voucher) in fetch_vouchers(&runner.rpc, &fanout)
+    .await
+    .context("failed to fetch vouchers")?
+  {
+    vouchers
+      .entry(voucher.wallet_share)
+      .or_default()
+      .push((key, voucher));
+  }
+  // Likewise vouchers from before any fields were added are grown before they are written to
+  let legacy_vouchers: HashSet<Pubkey> = fetch_legacy_vouchers(&runner.rpc, &fanout)
+    .await?
+    .into_iter()
+    .collect();
+  let migrate_voucher = |voucher: &Pubkey| {
+    legacy_vouchers
+      .contains(voucher)
+      .then(|| ix::migrate_voucher_v0(&payer, voucher))
+  };
+
+  // Share changes settle every existing inflow, vouchers are created for those plus any requested
+  let inflows = runner.inflows(&fanout).await?;
+  let mut voucher_inflows = inflows.clone();
+  let mut requested: Vec<Option<Pubkey>> = args.mints.into_iter().map(Some).collect();
+  if args.sol {
+    requested.push(None);
+  }
+  for mint in requested {
+    let mint_key = mint.unwrap_or_default();
+    if !voucher_inflows.iter().any(|inflow| inflow.mint == mint_key) {
+      voucher_inflows.push(runner.inflow(mint).await?);
+    }
+  }
+  let token_programs: HashMap<Pubkey, Pubkey> = voucher_inflows
+    .iter()
+    .map(|inflow| (inflow.mint, inflow.token_program))
+    .collect();
+
+  let desired = assign_indexes(&entries, &wallet_shares, fanout_acc.next_share_id);
+  let total_shares_issued = shares_issued(&desired, fanout_acc.total_shares)?;
+
+  // Inflows can run on their own cron job, and vouchers created before that stay on the fanout's
+  let mut voucher_cron_jobs: HashMap<Pubkey, Pubkey> = HashMap::new();
+  for inflow in &voucher_inflows {
+    let cron_job = runner
+      .voucher_cron_job(&fanout, &fanout_acc, &inflow.mint)
+      .await?;
+    voucher_cron_jobs.insert(inflow.mint, cron_job);
+  }
+  let mut scheduled_cron_jobs: HashMap<Pubkey, Pubkey> = HashMap::new();
+  for (voucher, voucher_acc) in vouchers.values().flatten() {
+    let cron_job = runner
+      .scheduled_cron_job(&fanout, &fanout_acc, voucher_acc)
+      .await?;
+    scheduled_cron_jobs.insert(*voucher, cron_job);
+  }
+
+  let claim = |voucher: &Pubkey, voucher_acc: &VoucherV0| {
+    if voucher_acc.mint == Pubkey::default() {
+      ix::claim_sol_v0(&fanout, voucher, voucher_acc)
+    } else {
+      ix::claim_v0(
+        &fanout,
+        &scheduled_cron_jobs[voucher],
+        voucher,
+        voucher_acc,
+        &token_programs[&voucher_acc.mint],
+      )
+    }
+  };
+  let set_share = |index: u32, entry: &ManifestEntry| {
+    ix::update_wallet_share_v0(
+      &payer,
+      &payer,
+      &fanout,
+      &entry.wallet,
+      &inflows,
+      UpdateWalletShareV0Args {
+        shares: entry.shares,
+        index,
+      },
+    )
+  };
+
+  let mut share_steps = vec![];
+  let mut voucher_closes = vec![];
+  let mut rotations = vec![];
+  for (change, index) in share_changes(&wallet_shares, &desired) {
+    if change == ShareChange::Remove {
+      let (wallet_share, wallet_share_acc) = &wallet_shares[&index];
+      if wallet_share_acc.suspended {
+        bail!("share {index} is suspended and can't be removed until it is unsuspended");
+      }
+      let share_vouchers = vouchers
+        .get(wallet_share)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+      let mut instructions: Vec<Instruction> = share_vouchers
+        .iter()
+        .filter_map(|(voucher, _)| migrate_voucher(voucher))
+        .chain(
+          share_vouchers
+            .iter()
+            .map(|(voucher, voucher_acc)| claim(voucher, voucher_acc)),
+        )
+        .chain(migrate(wallet_share))
+        .collect();
+      instructions.push(ix::close_wallet_share_v0(
+        &payer,
+        &fanout,
+        wallet_share,
+        &wallet_share_acc.rent_refund,
+        &inflows,
+      ));
+      share_steps.push(Step {
+        action: json!({
+          "action": "remove_share",
+          "index": index,
+          "wallet": wallet_share_acc.wallet.to_string(),
+          "shares": wallet_share_acc.shares,
+        }),
+        instructions,
+      });
+
+      for (voucher, voucher_acc) in share_vouchers {
+        let cron_job = scheduled_cron_jobs[voucher];
+        let instruction = if voucher_acc.mint == Pubkey::default() {
+          ix::close_sol_voucher_v0(&payer, &payer, &fanout, &cron_job, voucher, voucher_acc)
+        } else {
+          ix::close_voucher_v0(
+            &payer,
+            &payer,
+            &fanout,
+            &cron_job,
+            voucher,
+            voucher_acc,
+            &token_programs[&voucher_acc.mint],
+          )
+        };
+        fanout_acc.return_cron_transaction_id_to_pool(voucher_acc.cron_transaction_id);
+        voucher_closes.push(Step {
+          action: json!({
+            "action": "close_voucher",
+            "index": index,
+            "mint": voucher_acc.mint.to_string(),
+          }),
+          instructions: vec![instruction],
+        });
+      }
+      continue;
+    }
+
+    let entry = &desired[&index];
+    let Some((wallet_share, wallet_share_acc)) = wallet_shares.get(&index) else {
+      share_steps.push(Step {
+        action: json!({
+          "action": "add_share",
+          "index": index,
+          "wallet": entry.wallet.to_string(),
+          "shares": entry.shares,
+        }),
+        instructions: vec![set_share(index, entry)],
+      });
+      continue;
+    };
+    share_steps.push(Step {
+      action: json!({
+        "action": "update_share",
+        "index": index,
+        "old_wallet": wallet_share_acc.wallet.to_string(),
+        "wallet": entry.wallet.to_string(),
+        "old_shares": wallet_share_acc.shares,
+        "shares": entry.shares,
+      }),
+      instructions: migrate(wallet_share)
+        .into_iter()
+        .chain([set_share(index, entry)])
+        .collect(),
+    });
+
+    // Vouchers pay out to the wallet they were created for until rotated
+    if wallet_share_acc.wallet != entry.wallet {
+      for (voucher, voucher_acc) in vouchers.get(wallet_share).into_iter().flatten() {
+        // Rotating reschedules the voucher, so it moves onto its inflow's own cron job here
+        let cron_job = voucher_cron_jobs[&voucher_acc.mint];
+        let mut instructions: Vec<Instruction> = migrate_voucher(voucher).into_iter().collect();
+        if scheduled_cron_jobs[voucher] != cron_job {
+          instructions.push(ix::unschedule_voucher_v0(
+            &fanout,
+            &scheduled_cron_jobs[voucher],
+            voucher,
+            voucher_acc,
+          ));
+        }
+        instructions.push(if voucher_acc.mint == Pubkey::default() {
+          ix::rotate_sol_voucher_wallet_v0(&payer, &fanout, &cron_job, voucher, voucher_acc)
+        } else {
+          ix::rotate_voucher_wallet_v0(
+            &payer,
+            &fanout,
+            &cron_job,
+            voucher,
+            voucher_acc,
+            &token_programs[&voucher_acc.mint],
+          )
+        });
+        rotations.push(Step {
+          action: json!({
+            "action": "rotate_voucher",
+            "index": index,
+            "mint": voucher_acc.mint.to_string(),
+            "wallet": entry.wallet.to_string(),
+          }),
+          instructions,
+        });
+      }
+    }
+  }
+
+  let mut voucher_creates = vec![];
+  for index in desired.keys() {
+    let wallet_share = wallet_share_key(&fanout, *index).0;
+    for inflow in &voucher_inflows {
+      let exists = vouchers.get(&wallet_share).is_some_and(|share_vouchers| {
+        share_vouchers
+          .iter()
+          .any(|(_, voucher_acc)| voucher_acc.mint == inflow.mint)
+      });
+      if exists {
+        continue;
+      }
+      voucher_creates.push(Step {
+        action: json!({
+          "action": "create_voucher",
+          "index": index,
+          "mint": inflow.mint.to_string(),
+          "voucher": voucher_key(&fanout, &inflow.mint, &wallet_share).0.to_string(),
+          "cron_transaction_id": next_cron_transaction_id(&fanout_acc),
+        }),
+        instructions: vec![create_voucher(
+          &payer,
+          &fanout,
+          &fanout_acc,
+          &voucher_cron_jobs[&inflow.mint],
+          &wallet_share,
+          inflow,
+        )],
+      });
+      fanout_acc.get_next_cron_transaction_id();
+    }
+  }
+
+  let steps: Vec<Step> = share_steps
+    .into_iter()
+    .chain(rotations)
+    .chain(voucher_closes)
+    .chain(voucher_creates)
+    .collect();
+  let actions: Vec<&Value> = steps.iter().map(|step| &step.action).collect();
+  let batches = pack(&steps, &payer, args.batch_size);
+
+  let mut signatures = vec![];
+  // Later transactions depend on earlier ones, so only the first can be simulated up front
+  let mut simulation = Value::Null;
+  if runner.dry_run {
+    if let Some(batch) = batches.first() {
+      simulation = runner.execute(batch).await?;
+    }
+  } else {
+    for (i, batch) in batches.iter().enumerate() {
+      let result = runner.execute(batch).await.with_context(|| {
+        format!(
+          "transaction {} of {} failed, run reconcile again to apply the rest",
+          i + 1,
+          batches.len()
+        )
+      })?;
+      signatures.push(result["signature"].clone());
+    }
+  }
+
+  let result = json!({
+    "fanout": fanout.to_string(),
+    "dry_run": runner.dry_run,
+    "total_shares_issued": total_shares_issued,
+    "actions": actions,
+    "transactions": batches.len(),
+    "signatures": signatures,
+    "simulation": simulation,
+  });
+  if runner.json {
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    return Ok(());
+  }
+
+  if actions.is_empty() {
+    println!("Fanout {} already matches the manifest", args.fanout);
+    return Ok(());
+  }
+  for action in &actions {
+    let mut fields = action.as_object().cloned().unwrap_or_default();
+    let name = fields.remove("action").unwrap_or_default();
+    let fields: Vec<String> = fields
+      .iter()
+      .map(|(key, value)| {
+        format!(
+          "{key}={}",
+          value.as_str().map_or(value.to_string(), str::to_string)
+        )
+      })
+      .collect();
+    println!("{} {}", name.as_str().unwrap_or_default(), fields.join(" "));
+  }
+  if runner.dry_run {
+    runner.report(
+      &format!(
+        "simulated the first of {} transactions, none were sent",
+        batches.len()
+      ),
+      simulation,
+    )?;
+  } else {
+    for signature in &signatures {
+      println!("Sent {}", signature.as_str().unwrap_or_default());
+    }
+  }
+  Ok(())
+}
+
+/// How a wallet share changes, in the order the changes are sent. Shares only shrink before they
+/// grow, so total_shares_issued never overshoots in between
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ShareChange {
+  Remove,
+  Shrink,
+  /// New shares, and shares that only change wallet
+  Grow,
+}
+
+/// The wallet share changes that take `wallet_shares` to `desired`, in the order they are sent
+fn share_changes(
+  wallet_shares: &BTreeMap<u32, (Pubkey, WalletShareV0)>,
+  desired: &BTreeMap<u32, ManifestEntry>,
+) -> Vec<(ShareChange, u32)> {
+  let removals = wallet_shares
+    .keys()
+    .filter(|index| !desired.contains_key(index))
+    .map(|index| (ShareChange::Remove, *index));
+  let updates = desired.iter().filter_map(|(index, entry)| {
+    let Some((_, wallet_share)) = wallet_shares.get(index) else {
+      return Some((ShareChange::Grow, *index));
+    };
+    if wallet_share.wallet == entry.wallet && wallet_share.shares == entry.shares {
+      None
+    } else if entry.shares < wallet_share.shares {
+      Some((ShareChange::Shrink, *index))
+    } else {
+      Some((ShareChange::Grow, *index))
+    }
+  });
+  let mut changes: Vec<(ShareChange, u32)> = removals.chain(updates).collect();
+  changes.sort();
+  changes
+}
+
+/// The total shares `desired` issues, which can't be more than the fanout has
+fn shares_issued(desired: &BTreeMap<u32, ManifestEntry>, total_shares: u32) -> Result<u64> {
+  let total_shares_issued: u64 = desired.values().map(|entry| u64::from(entry.shares)).sum();
+  if total_shares_issued > u64::from(total_shares) {
+    bail!("manifest issues {total_shares_issued} shares but the fanout only has {total_shares}");
+  }
+  Ok(total_shares_issued)
+}
+
+/// Matches manifest entries to wallet share indexes. Entries without an index take over the
+/// wallet's existing share, or get an index that was never used
+fn assign_indexes(
+  entries: &[ManifestEntry],
+  wallet_shares: &BTreeMap<u32, (Pubkey, WalletShareV0)>,
+  next_share_id: u32,
+) -> BTreeMap<u32, ManifestEntry> {
+  let mut desired: BTreeMap<u32, ManifestEntry> = entries
+    .iter()
+    .filter_map(|entry| Some((entry.index?, entry.clone())))
+    .collect();
+
+  let mut next_index = next_share_id;
+  for entry in entries.iter().filter(|entry| entry.index.is_none()) {
+    let existing = wallet_shares
+      .iter()
+      .find(|(index, (_, wallet_share))| {
+        wallet_share.wallet == entry.wallet && !desired.contains_key(index)
+      })
+      .map(|(index, _)| *index);
+    let index = existing.unwrap_or_else(|| {
+      while desired.contains_key(&next_index) || wallet_shares.contains_key(&next_index) {
+        next_index += 1;
+      }
+      next_index
+    });
+    desired.insert(index, entry.clone());
+  }
+  desired
+}
+
+fn create_voucher(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  fanout_acc: &wallet_fanout_client::FanoutV0,
+  cron_job: &Pubkey,
+  wallet_share: &Pubkey,
+  inflow: &Inflow,
+) -> Instruction {
+  if inflow.mint == Pubkey::default() {
+    ix::initialize_sol_voucher_v0(payer, fanout, fanout_acc, cron_job, wallet_share)
+  } else {
+    ix::initialize_voucher_v0(
+      payer,
+      fanout,
+      fanout_acc,
+      cron_job,
+      wallet_share,
+      &inflow.mint,
+      &inflow.token_program,
+    )
+  }
+}
+
+fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+  let tx = Transaction::new_with_payer(instructions, Some(payer));
+  // Compact length prefix plus one signature per signer, followed by the message
+  1 + 64 * tx.signatures.len() + tx.message_data().len()
+}
+
+/// Groups steps into transactions of at most `batch_size` steps that fit the size limit. A step
+/// too large on its own still gets its own transaction, and fails when sent
+fn pack(steps: &[Step], payer: &Pubkey, batch_size: usize) -> Vec<Vec<Instruction>> {
+  let mut batches = vec![];
+  let mut batch: Vec<Instruction> = vec![];
+  let mut batch_steps = 0;
+  for step in steps {
+    let mut candidate = batch.clone();
+    candidate.extend(step.instructions.iter().cloned());
+    if batch_steps > 0
+      && (batch_steps >= batch_size || transaction_size(&candidate, payer) > MAX_TRANSACTION_SIZE)
+    {
+      batches.push(std::mem::take(&mut batch));
+      batch_steps = 0;
+      candidate = step.instructions.clone();
+    }
+    batch = candidate;
+    batch_steps += 1;
+  }
+  if !batch.is_empty() {
+    batches.push(batch);
+  }
+  batches
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(wallet: Pubkey, shares: u32, index: Option<u32>) -> ManifestEntry {
+    ManifestEntry {
+      wallet,
+      shares,
+      index,
+    }
+  }
+
+  fn wallet_shares(shares: &[(u32, Pubkey, u32)]) -> BTreeMap<u32, (Pubkey, WalletShareV0)> {
+    shares
+      .iter()
+      .map(|(index, wallet, shares)| {
+        let wallet_share = WalletShareV0 {
+          id: *index,
+          wallet: *wallet,
+          shares: *shares,
+          ..Default::default()
+        };
+        (*index, (Pubkey::new_unique(), wallet_share))
+      })
+      .collect()
+  }
+
+  fn step(data_len: usize) -> Step {
+    Step {
+      action: Value::Null,
+      instructions: vec![Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &vec![0; data_len],
+        vec![],
+      )],
+    }
+  }
+
+  fn batch_lens(batches: &[Vec<Instruction>]) -> Vec<usize> {
+    batches.iter().map(Vec::len).collect()
+  }
+
+  #[test]
+  fn reuses_the_wallets_existing_index() {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let existing = wallet_shares(&[(0, a, 10), (1, b, 10)]);
+
+    let desired = assign_indexes(&[entry(b, 20, None), entry(a, 5, None)], &existing, 2);
+
+    assert_eq!(desired[&0].wallet, a);
+    assert_eq!(desired[&1].wallet, b);
+    assert_eq!(desired.len(), 2);
+  }
+
+  #[test]
+  fn allocates_indexes_that_were_never_used() {
+    let (a, b, c) = (
+      Pubkey::new_unique(),
+      Pubkey::new_unique(),
+      Pubkey::new_unique(),
+    );
+    // Index 1 was closed, index 3 is taken by an explicit entry
+    let existing = wallet_shares(&[(0, a, 10), (2, b, 10)]);
+
+    let desired = assign_indexes(&[entry(c, 10, None), entry(a, 10, Some(3))], &existing, 3);
+
+    assert_eq!(desired[&3].wallet, a);
+    assert_eq!(desired[&4].wallet, c);
+    assert_eq!(desired.len(), 2);
+  }
+
+  #[test]
+  fn explicit_index_keeps_the_wallet_from_its_old_share() {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let existing = wallet_shares(&[(0, a, 10)]);
+
+    let desired = assign_indexes(&[entry(a, 10, None), entry(b, 10, Some(0))], &existing, 1);
+
+    assert_eq!(desired[&0].wallet, b);
+    assert_eq!(desired[&1].wallet, a);
+  }
+
+  #[test]
+  fn duplicate_wallets_get_a_share_each() {
+    let a = Pubkey::new_unique();
+    let existing = wallet_shares(&[(0, a, 10)]);
+
+    let desired = assign_indexes(
+      &[entry(a, 10, None), entry(a, 20, None), entry(a, 30, None)],
+      &existing,
+      1,
+    );
+
+    assert_eq!(
+      desired
+        .iter()
+        .map(|(index, entry)| (*index, entry.wallet, entry.shares))
+        .collect::<Vec<_>>(),
+      vec![(0, a, 10), (1, a, 20), (2, a, 30)]
+    );
+  }
+
+  #[test]
+  fn rejects_issuing_more_than_total_shares() {
+    let desired = BTreeMap::from([
+      (0, entry(Pubkey::new_unique(), 60, None)),
+      (1, entry(Pubkey::new_unique(), 50, None)),
+    ]);
+
+    assert_eq!(shares_issued(&desired, 110).unwrap(), 110);
+    let err = shares_issued(&desired, 100).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "manifest issues 110 shares but the fanout only has 100"
+    );
+  }
+
+  #[test]
+  fn shrinks_shares_before_growing_them() {
+    let (a, b, c, d, e) = (
+      Pubkey::new_unique(),
+      Pubkey::new_unique(),
+      Pubkey::new_unique(),
+      Pubkey::new_unique(),
+      Pubkey::new_unique(),
+    );
+    let existing = wallet_shares(&[(0, a, 10), (1, b, 30), (2, c, 10), (3, d, 20), (5, d, 10)]);
+    let desired = BTreeMap::from([
+      // Grows
+      (0, entry(a, 40, None)),
+      // Shrinks
+      (1, entry(b, 10, None)),
+      // Unchanged
+      (2, entry(c, 10, None)),
+      // Same shares for a new wallet
+      (3, entry(e, 20, None)),
+      // New, while index 5 is removed
+      (4, entry(e, 5, None)),
+    ]);
+
+    assert_eq!(
+      share_changes(&existing, &desired),
+      vec![
+        (ShareChange::Remove, 5),
+        (ShareChange::Shrink, 1),
+        (ShareChange::Grow, 0),
+        (ShareChange::Grow, 3),
+        (ShareChange::Grow, 4),
+      ]
+    );
+  }
+
+  #[test]
+  fn packs_at_most_batch_size_steps() {
+    let payer = Pubkey::new_unique();
+    let steps: Vec<Step> = (0..5).map(|_| step(1)).collect();
+
+    assert_eq!(batch_lens(&pack(&steps, &payer, 2)), vec![2, 2, 1]);
+    assert_eq!(batch_lens(&pack(&steps, &payer, 10)), vec![5]);
+    assert!(pack(&[], &payer, 2).is_empty());
+  }
+
+  #[test]
+  fn packs_within_the_transaction_size() {
+    let payer = Pubkey::new_unique();
+    let steps: Vec<Step> = (0..5).map(|_| step(500)).collect();
+
+    let batches = pack(&steps, &payer, 10);
+
+    assert_eq!(batch_lens(&batches), vec![2, 2, 1]);
+    for batch in &batches {
+      assert!(transaction_size(batch, &payer) <= MAX_TRANSACTION_SIZE);
+    }
+  }
+
+  #[test]
+  fn keeps_a_steps_instructions_together() {
+    let payer = Pubkey::new_unique();
+    let mut steps = vec![step(400), step(400)];
+    // Fits on its own, but not alongside the two before it
+    steps.push(Step {
+      action: Value::Null,
+      instructions: step(200)
+        .instructions
+        .into_iter()
+        .chain(step(200).instructions)
+        .collect(),
+    });
+    // Too large for any transaction, so it is sent on its own
+    steps.push(step(2000));
+    steps.push(step(1));
+
+    let batches = pack(&steps, &payer, 10);
+
+    assert_eq!(batch_lens(&batches), vec![2, 2, 1, 1]);
+    assert!(transaction_size(&batches[2], &payer) > MAX_TRANSACTION_SIZE);
+  }
+}
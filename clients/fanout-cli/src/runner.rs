@@ -0,0 +1,204 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use solana_keypair::Keypair;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use tuktuk_program::TaskQueueV0;
+use wallet_fanout_client::{
+  accounts::{fetch, fetch_optional, fetch_token_inflow, fetch_token_inflows},
+  instructions::{self as ix, Inflow},
+  pdas::{cron_job_transaction_key, fanout_key, queue_authority_key, user_cron_jobs_key},
+  FanoutV0, VoucherV0, WalletShareV0,
+};
+
+/// Shared state for running a command: where to send transactions, who pays for them and how to
+/// report the result
+pub struct Runner {
+  pub rpc: RpcClient,
+  pub payer: Keypair,
+  pub dry_run: bool,
+  pub json: bool,
+}
+
+impl Runner {
+  pub fn payer(&self) -> Pubkey {
+    self.payer.pubkey()
+  }
+
+  /// Sends the instructions as a single transaction, or only simulates it with `--dry-run`
+  pub async fn execute(&self, instructions: &[Instruction]) -> Result<Value> {
+    let blockhash = self
+      .rpc
+      .get_latest_blockhash()
+      .await
+      .context("failed to fetch blockhash")?;
+    let tx = Transaction::new_signed_with_payer(
+      instructions,
+      Some(&self.payer()),
+      &[&self.payer],
+      blockhash,
+    );
+
+    if self.dry_run {
+      let simulation = self
+        .rpc
+        .simulate_transaction_with_config(
+          &tx,
+          RpcSimulateTransactionConfig {
+            sig_verify: true,
+            commitment: Some(self.rpc.commitment()),
+            ..Default::default()
+          },
+        )
+        .await
+        .context("failed to simulate transaction")?
+        .value;
+      return Ok(json!({
+        "dry_run": true,
+        "err": simulation.err.map(|err| err.to_string()),
+        "units_consumed": simulation.units_consumed,
+        "logs": simulation.logs.unwrap_or_default(),
+      }));
+    }
+
+    let signature = self
+      .rpc
+      .send_and_confirm_transaction(&tx)
+      .await
+      .context("failed to send transaction")?;
+    Ok(json!({ "signature": signature.to_string() }))
+  }
+
+  /// Prints a command's result, as JSON with `--json`
+  pub fn report(&self, message: &str, result: Value) -> Result<()> {
+    if self.json {
+      println!("{}", serde_json::to_string_pretty(&result)?);
+      return Ok(());
+    }
+
+    if result["dry_run"].as_bool().unwrap_or(false) {
+      match result["err"].as_str() {
+        Some(err) => println!("Simulation failed: {err}"),
+        None => println!("Simulation succeeded: {message}"),
+      }
+      if let Some(units) = result["units_consumed"].as_u64() {
+        println!("Compute units: {units}");
+      }
+      for log in result["logs"].as_array().into_iter().flatten() {
+        println!("  {}", log.as_str().unwrap_or_default());
+      }
+    } else if let Some(signature) = result["signature"].as_str() {
+      println!("{message}: {signature}");
+    } else {
+      println!("{message}");
+    }
+    Ok(())
+  }
+
+  pub async fn fanout(&self, name: &str) -> Result<(Pubkey, FanoutV0)> {
+    let fanout = fanout_key(name).0;
+    let fanout_acc = fetch_optional(&self.rpc, &fanout)
+      .await?
+      .ok_or_else(|| anyhow!("fanout {name} does not exist"))?;
+    Ok((fanout, fanout_acc))
+  }
+
+  /// The program that owns `mint`, which is the token program its accounts use
+  pub async fn token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+    Ok(
+      self
+        .rpc
+        .get_account(mint)
+        .await
+        .with_context(|| format!("failed to fetch mint {mint}"))?
+        .owner,
+    )
+  }
+
+  pub async fn inflow(&self, mint: Option<Pubkey>) -> Result<Inflow> {
+    Ok(match mint {
+      Some(mint) => Inflow {
+        mint,
+        token_program: self.token_program(&mint).await?,
+      },
+      None => Inflow::sol(),
+    })
+  }
+
+  /// Every inflow of the fanout, for instructions that settle them before changing shares
+  pub async fn inflows(&self, fanout: &Pubkey) -> Result<Vec<Inflow>> {
+    let mut inflows = vec![];
+    for (_, token_inflow) in fetch_token_inflows(&self.rpc, fanout).await? {
+      let mint = (token_inflow.mint != Pubkey::default()).then_some(token_inflow.mint);
+      inflows.push(self.inflow(mint).await?);
+    }
+    Ok(inflows)
+  }
+
+  /// The cron job new vouchers of `mint` are scheduled on: the inflow's own if it has one,
+  /// otherwise the fanout's
+  pub async fn voucher_cron_job(
+    &self,
+    fanout: &Pubkey,
+    fanout_acc: &FanoutV0,
+    mint: &Pubkey,
+  ) -> Result<Pubkey> {
+    Ok(
+      fetch_token_inflow(&self.rpc, fanout, mint)
+        .await?
+        .map_or(fanout_acc.cron_job, |token_inflow| {
+          token_inflow.voucher_cron_job(fanout_acc)
+        }),
+    )
+  }
+
+  /// The cron job `voucher_acc`'s claim is currently on. Vouchers created before their inflow got
+  /// its own cron job stay on the fanout's until they are moved
+  pub async fn scheduled_cron_job(
+    &self,
+    fanout: &Pubkey,
+    fanout_acc: &FanoutV0,
+    voucher_acc: &VoucherV0,
+  ) -> Result<Pubkey> {
+    let cron_job = self
+      .voucher_cron_job(fanout, fanout_acc, &voucher_acc.mint)
+      .await?;
+    let transaction = cron_job_transaction_key(&cron_job, voucher_acc.cron_transaction_id).0;
+    if cron_job == fanout_acc.cron_job || self.rpc.get_balance(&transaction).await? > 0 {
+      Ok(cron_job)
+    } else {
+      Ok(fanout_acc.cron_job)
+    }
+  }
+
+  /// migrate_wallet_share_v0 for `wallet_share` if it is from before wallet shares could be
+  /// suspended, as those have to be grown before they can be written to
+  pub async fn migrate_wallet_share(&self, wallet_share: &Pubkey) -> Result<Option<Instruction>> {
+    let account = self
+      .rpc
+      .get_account_with_commitment(wallet_share, self.rpc.commitment())
+      .await?
+      .value;
+    Ok(
+      account
+        .filter(|account| account.data.len() == WalletShareV0::LEGACY_LEN)
+        .map(|_| ix::migrate_wallet_share_v0(&self.payer(), wallet_share)),
+    )
+  }
+
+  pub async fn next_task_id(&self, task_queue: &Pubkey) -> Result<u16> {
+    let task_queue_acc: TaskQueueV0 = fetch(&self.rpc, task_queue).await?;
+    task_queue_acc
+      .next_available_task_id()
+      .ok_or_else(|| anyhow!("task queue {task_queue} is full"))
+  }
+
+  pub async fn next_cron_job_id(&self) -> Result<u32> {
+    let user_cron_jobs: Option<tuktuk_program::cron::accounts::UserCronJobsV0> =
+      fetch_optional(&self.rpc, &user_cron_jobs_key(&queue_authority_key().0).0).await?;
+    Ok(user_cron_jobs.map_or(0, |user_cron_jobs| user_cron_jobs.next_cron_job_id))
+  }
+}
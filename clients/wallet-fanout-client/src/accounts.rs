@@ -0,0 +1,192 @@
+//! Fetching and decoding wallet-fanout accounts over RPC.
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+  config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+  filter::{Memcmp, RpcFilterType},
+};
+use wallet_fanout::{FanoutV0, GlobalStateV0, TokenInflowV0, VoucherV0, WalletShareV0};
+
+use crate::pdas::{fanout_key, global_state_key, token_inflow_key};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  #[error("rpc error: {0}")]
+  Rpc(Box<solana_rpc_client_api::client_error::Error>),
+  #[error("failed to decode account: {0}")]
+  Anchor(#[from] anchor_lang::error::Error),
+  #[error("account {0} not found")]
+  AccountNotFound(Pubkey),
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for Error {
+  fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+    Self::Rpc(Box::new(err))
+  }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Offsets of the `fanout` field, after the 8 byte discriminator
+const WALLET_SHARE_FANOUT_OFFSET: usize = 8;
+const TOKEN_INFLOW_FANOUT_OFFSET: usize = 8;
+const VOUCHER_FANOUT_OFFSET: usize = 8 + 32 + 32 + 4;
+
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+  let mut data = data;
+  Ok(T::try_deserialize(&mut data)?)
+}
+
+pub async fn fetch_optional<T: AccountDeserialize>(
+  rpc: &RpcClient,
+  key: &Pubkey,
+) -> Result<Option<T>> {
+  let account = rpc
+    .get_account_with_commitment(key, rpc.commitment())
+    .await?
+    .value;
+  account.map(|account| decode(&account.data)).transpose()
+}
+
+pub async fn fetch<T: AccountDeserialize>(rpc: &RpcClient, key: &Pubkey) -> Result<T> {
+  fetch_optional(rpc, key)
+    .await?
+    .ok_or(Error::AccountNotFound(*key))
+}
+
+pub async fn fetch_global_state(rpc: &RpcClient) -> Result<GlobalStateV0> {
+  fetch(rpc, &global_state_key().0).await
+}
+
+pub async fn fetch_fanout_by_name(rpc: &RpcClient, name: &str) -> Result<(Pubkey, FanoutV0)> {
+  let key = fanout_key(name).0;
+  Ok((key, fetch(rpc, &key).await?))
+}
+
+/// Use `Pubkey::default()` as the mint for the SOL inflow
+pub async fn fetch_token_inflow(
+  rpc: &RpcClient,
+  fanout: &Pubkey,
+  mint: &Pubkey,
+) -> Result<Option<TokenInflowV0>> {
+  fetch_optional(rpc, &token_inflow_key(fanout, mint).0).await
+}
+
+async fn fetch_program_accounts<T: AccountDeserialize + Discriminator>(
+  rpc: &RpcClient,
+  fanout: &Pubkey,
+  fanout_offset: usize,
+) -> Result<Vec<(Pubkey, T)>> {
+  fetch_program_accounts_with::<T, T>(rpc, fanout, fanout_offset, vec![], decode).await
+}
+
+/// Fetches the `D` accounts of `fanout` matching `filters`, decoded with `decode`
+async fn fetch_program_accounts_with<D: Discriminator, T>(
+  rpc: &RpcClient,
+  fanout: &Pubkey,
+  fanout_offset: usize,
+  filters: Vec<RpcFilterType>,
+  decode: impl Fn(&[u8]) -> Result<T>,
+) -> Result<Vec<(Pubkey, T)>> {
+  let config = RpcProgramAccountsConfig {
+    filters: Some(
+      [
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, D::DISCRIMINATOR)),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(fanout_offset, fanout.as_ref())),
+      ]
+      .into_iter()
+      .chain(filters)
+      .collect(),
+    ),
+    account_config: RpcAccountInfoConfig {
+      encoding: Some(UiAccountEncoding::Base64),
+      commitment: Some(rpc.commitment()),
+      ..Default::default()
+    },
+    ..Default::default()
+  };
+  rpc
+    .get_program_accounts_with_config(&wallet_fanout::ID, config)
+    .await?
+    .into_iter()
+    .map(|(key, account)| Ok((key, decode(&account.data)?)))
+    .collect()
+}
+
+/// Wallet shares not yet grown by migrate_wallet_share_v0 decode as not suspended
+pub async fn fetch_wallet_share(rpc: &RpcClient, key: &Pubkey) -> Result<WalletShareV0> {
+  let account = rpc
+    .get_account_with_commitment(key, rpc.commitment())
+    .await?
+    .value
+    .ok_or(Error::AccountNotFound(*key))?;
+  Ok(WalletShareV0::try_deserialize_any(&account.data)?)
+}
+
+/// Wallet shares not yet grown by migrate_wallet_share_v0 decode as not suspended
+pub async fn fetch_wallet_shares(
+  rpc: &RpcClient,
+  fanout: &Pubkey,
+) -> Result<Vec<(Pubkey, WalletShareV0)>> {
+  fetch_program_accounts_with::<WalletShareV0, _>(
+    rpc,
+    fanout,
+    WALLET_SHARE_FANOUT_OFFSET,
+    vec![],
+    |data| Ok(WalletShareV0::try_deserialize_any(data)?),
+  )
+  .await
+}
+
+/// Wallet shares that have to be grown by migrate_wallet_share_v0 before they can be updated,
+/// suspended or closed
+pub async fn fetch_legacy_wallet_shares(rpc: &RpcClient, fanout: &Pubkey) -> Result<Vec<Pubkey>> {
+  Ok(
+    fetch_program_accounts_with::<WalletShareV0, _>(
+      rpc,
+      fanout,
+      WALLET_SHARE_FANOUT_OFFSET,
+      vec![RpcFilterType::DataSize(WalletShareV0::LEGACY_LEN as u64)],
+      |_| Ok(()),
+    )
+    .await?
+    .into_iter()
+    .map(|(key, _)| key)
+    .collect(),
+  )
+}
+
+pub async fn fetch_token_inflows(
+  rpc: &RpcClient,
+  fanout: &Pubkey,
+) -> Result<Vec<(Pubkey, TokenInflowV0)>> {
+  fetch_program_accounts(rpc, fanout, TOKEN_INFLOW_FANOUT_OFFSET).await
+}
+
+/// Vouchers not yet grown by migrate_voucher_v0 decode as they will once migrated
+pub async fn fetch_vouchers(rpc: &RpcClient, fanout: &Pubkey) -> Result<Vec<(Pubkey, VoucherV0)>> {
+  fetch_program_accounts_with::<VoucherV0, _>(rpc, fanout, VOUCHER_FANOUT_OFFSET, vec![], |data| {
+    Ok(VoucherV0::try_deserialize_any(data)?)
+  })
+  .await
+}
+
+/// Vouchers that have to be grown by migrate_voucher_v0 before they can be claimed, rotated or
+/// closed
+pub async fn fetch_legacy_vouchers(rpc: &RpcClient, fanout: &Pubkey) -> Result<Vec<Pubkey>> {
+  Ok(
+    fetch_program_accounts_with::<VoucherV0, _>(
+      rpc,
+      fanout,
+      VOUCHER_FANOUT_OFFSET,
+      vec![RpcFilterType::DataSize(VoucherV0::LEGACY_LEN as u64)],
+      |_| Ok(()),
+    )
+    .await?
+    .into_iter()
+    .map(|(key, _)| key)
+    .collect(),
+  )
+}
@@ -0,0 +1,1540 @@
+//! Instruction builders for every wallet-fanout handler. Program derived addresses are filled in, so
+//! callers only pass the keys and state that can't be derived.
+
+use anchor_lang::{
+  prelude::*, solana_program::instruction::Instruction, system_program, InstructionData,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use tuktuk_program::{cron, tuktuk};
+use wallet_fanout::{
+  accounts, instruction, token_inflow_cron_job_name, FanoutV0, ForfeitVoucherV0Args,
+  InitializeFanoutV0Args, InitializeTokenInflowCronJobV0Args, ProposeFanoutAuthorityV0Args,
+  RequeueFanoutTaskArgsV0, UnsuspendWalletShareV0Args, UpdateFanoutScheduleV0Args,
+  UpdateFanoutV0Args, UpdateGlobalStateV0Args, UpdateTokenInflowV0Args, UpdateTotalSharesV0Args,
+  UpdateUnissuedSharePolicyV0Args, UpdateVoucherV0Args, UpdateWalletShareV0Args, VoucherV0,
+};
+
+use crate::pdas::*;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+  Instruction {
+    program_id: wallet_fanout::ID,
+    accounts: accounts.to_account_metas(None),
+    data: data.data(),
+  }
+}
+
+fn build_with_remaining(
+  accounts: impl ToAccountMetas,
+  data: impl InstructionData,
+  remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+  let mut ix = build(accounts, data);
+  ix.accounts.extend(remaining_accounts);
+  ix
+}
+
+/// One of a fanout's token inflows. Instructions that change how inflow is split need every inflow
+/// of the fanout passed as remaining accounts.
+#[derive(Clone, Copy, Debug)]
+pub struct Inflow {
+  /// `Pubkey::default()` for SOL
+  pub mint: Pubkey,
+  pub token_program: Pubkey,
+}
+
+impl Inflow {
+  pub fn sol() -> Self {
+    Self {
+      mint: Pubkey::default(),
+      token_program: Pubkey::default(),
+    }
+  }
+
+  /// The account holding this inflow's balance: the fanout's token account, or the fanout itself
+  /// for SOL
+  pub fn balance_account(&self, fanout: &Pubkey) -> Pubkey {
+    if self.mint == Pubkey::default() {
+      *fanout
+    } else {
+      get_associated_token_address_with_program_id(fanout, &self.mint, &self.token_program)
+    }
+  }
+}
+
+/// `[token_inflow, balance_account]` pairs for update_total_shares_v0 and
+/// update_unissued_share_policy_v0
+pub fn settle_inflow_accounts(fanout: &Pubkey, inflows: &[Inflow]) -> Vec<AccountMeta> {
+  inflows
+    .iter()
+    .flat_map(|inflow| {
+      [
+        AccountMeta::new(token_inflow_key(fanout, &inflow.mint).0, false),
+        AccountMeta::new_readonly(inflow.balance_account(fanout), false),
+      ]
+    })
+    .collect()
+}
+
+/// `[token_inflow, balance_account, voucher]` triples for update_wallet_share_v0,
+/// unsuspend_wallet_share_v0 and close_wallet_share_v0
+pub fn settle_wallet_share_accounts(
+  fanout: &Pubkey,
+  wallet_share: &Pubkey,
+  inflows: &[Inflow],
+) -> Vec<AccountMeta> {
+  inflows
+    .iter()
+    .flat_map(|inflow| {
+      [
+        AccountMeta::new(token_inflow_key(fanout, &inflow.mint).0, false),
+        AccountMeta::new_readonly(inflow.balance_account(fanout), false),
+        AccountMeta::new(voucher_key(fanout, &inflow.mint, wallet_share).0, false),
+      ]
+    })
+    .collect()
+}
+
+/// The cron transaction index initialize_voucher_v0 and initialize_sol_voucher_v0 will use
+pub fn next_cron_transaction_id(fanout: &FanoutV0) -> u32 {
+  fanout
+    .available_cron_transaction_ids
+    .last()
+    .copied()
+    .unwrap_or(fanout.next_cron_transaction_id)
+}
+
+pub fn initialize_global_state_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  task_queue: &Pubkey,
+) -> Instruction {
+  let queue_authority = queue_authority_key().0;
+  build(
+    accounts::InitializeGlobalStateV0 {
+      payer: *payer,
+      global_state: global_state_key().0,
+      queue_authority,
+      task_queue: *task_queue,
+      task_queue_authority: task_queue_authority_key(task_queue, &queue_authority).0,
+      authority: *authority,
+      system_program: system_program::ID,
+    },
+    instruction::InitializeGlobalStateV0 {},
+  )
+}
+
+/// Pass `task_queue` to move new cron jobs to a different task queue
+pub fn update_global_state_v0(
+  authority: &Pubkey,
+  task_queue: Option<&Pubkey>,
+  args: UpdateGlobalStateV0Args,
+) -> Instruction {
+  let queue_authority = queue_authority_key().0;
+  build(
+    accounts::UpdateGlobalStateV0 {
+      authority: *authority,
+      global_state: global_state_key().0,
+      queue_authority,
+      task_queue: task_queue.copied(),
+      task_queue_authority: task_queue
+        .map(|task_queue| task_queue_authority_key(task_queue, &queue_authority).0),
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UpdateGlobalStateV0 { args },
+  )
+}
+
+pub fn accept_global_state_authority_v0(pending_authority: &Pubkey) -> Instruction {
+  build(
+    accounts::AcceptGlobalStateAuthorityV0 {
+      pending_authority: *pending_authority,
+      global_state: global_state_key().0,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::AcceptGlobalStateAuthorityV0 {},
+  )
+}
+
+/// `cron_job_id` is the queue authority's next cron job id and `task_id` a free task on the task
+/// queue
+pub fn initialize_fanout_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  task_queue: &Pubkey,
+  cron_job_id: u32,
+  task_id: u16,
+  args: InitializeFanoutV0Args,
+) -> Instruction {
+  let queue_authority = queue_authority_key().0;
+  let cron_job = cron_job_key(&queue_authority, cron_job_id).0;
+  build(
+    accounts::InitializeFanoutV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: fanout_key(&args.name).0,
+      global_state: global_state_key().0,
+      task_queue: *task_queue,
+      task_queue_authority: task_queue_authority_key(task_queue, &queue_authority).0,
+      queue_authority,
+      user_cron_jobs: user_cron_jobs_key(&queue_authority).0,
+      cron_job,
+      cron_job_name_mapping: cron_job_name_mapping_key(&queue_authority, &args.name).0,
+      task: task_key(task_queue, task_id).0,
+      task_return_account_1: task_return_account_1_key(&cron_job).0,
+      task_return_account_2: task_return_account_2_key(&cron_job).0,
+      system_program: system_program::ID,
+      tuktuk_program: tuktuk::ID,
+      cron_program: cron::ID,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::InitializeFanoutV0 { args },
+  )
+}
+
+pub fn update_fanout_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  args: UpdateFanoutV0Args,
+) -> Instruction {
+  build(
+    accounts::UpdateFanoutV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: *fanout,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UpdateFanoutV0 { args },
+  )
+}
+
+pub fn update_fanout_schedule_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  args: UpdateFanoutScheduleV0Args,
+) -> Instruction {
+  build(
+    accounts::UpdateFanoutScheduleV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: *fanout,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UpdateFanoutScheduleV0 { args },
+  )
+}
+
+pub fn update_unissued_share_policy_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  inflows: &[Inflow],
+  args: UpdateUnissuedSharePolicyV0Args,
+) -> Instruction {
+  build_with_remaining(
+    accounts::UpdateUnissuedSharePolicyV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: *fanout,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UpdateUnissuedSharePolicyV0 { args },
+    settle_inflow_accounts(fanout, inflows),
+  )
+}
+
+/// Pass `mint` to pause only that inflow, with `Pubkey::default()` for SOL
+pub fn pause_fanout_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  mint: Option<&Pubkey>,
+) -> Instruction {
+  build(
+    accounts::PauseFanoutV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: *fanout,
+      token_inflow: mint.map(|mint| token_inflow_key(fanout, mint).0),
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::PauseFanoutV0 {},
+  )
+}
+
+/// Pass `mint` to resume only that inflow, with `Pubkey::default()` for SOL
+pub fn resume_fanout_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  mint: Option<&Pubkey>,
+) -> Instruction {
+  build(
+    accounts::ResumeFanoutV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: *fanout,
+      token_inflow: mint.map(|mint| token_inflow_key(fanout, mint).0),
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::ResumeFanoutV0 {},
+  )
+}
+
+pub fn propose_fanout_authority_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  args: ProposeFanoutAuthorityV0Args,
+) -> Instruction {
+  build(
+    accounts::ProposeFanoutAuthorityV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: *fanout,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::ProposeFanoutAuthorityV0 { args },
+  )
+}
+
+pub fn accept_fanout_authority_v0(pending_authority: &Pubkey, fanout: &Pubkey) -> Instruction {
+  build(
+    accounts::AcceptFanoutAuthorityV0 {
+      pending_authority: *pending_authority,
+      fanout: *fanout,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::AcceptFanoutAuthorityV0 {},
+  )
+}
+
+pub fn update_total_shares_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  inflows: &[Inflow],
+  args: UpdateTotalSharesV0Args,
+) -> Instruction {
+  build_with_remaining(
+    accounts::UpdateTotalSharesV0 {
+      authority: *authority,
+      fanout: *fanout,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UpdateTotalSharesV0 { args },
+    settle_inflow_accounts(fanout, inflows),
+  )
+}
+
+pub fn update_wallet_share_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  wallet: &Pubkey,
+  inflows: &[Inflow],
+  args: UpdateWalletShareV0Args,
+) -> Instruction {
+  let wallet_share = wallet_share_key(fanout, args.index).0;
+  build_with_remaining(
+    accounts::UpdateWalletShareV0 {
+      payer: *payer,
+      authority: *authority,
+      wallet: *wallet,
+      fanout: *fanout,
+      wallet_share,
+      system_program: system_program::ID,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::UpdateWalletShareV0 { args },
+    settle_wallet_share_accounts(fanout, &wallet_share, inflows),
+  )
+}
+
+pub fn suspend_wallet_share_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  wallet_share: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::SuspendWalletShareV0 {
+      authority: *authority,
+      fanout: *fanout,
+      wallet_share: *wallet_share,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::SuspendWalletShareV0 {},
+  )
+}
+
+pub fn unsuspend_wallet_share_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  wallet_share: &Pubkey,
+  inflows: &[Inflow],
+  args: UnsuspendWalletShareV0Args,
+) -> Instruction {
+  build_with_remaining(
+    accounts::UnsuspendWalletShareV0 {
+      authority: *authority,
+      fanout: *fanout,
+      wallet_share: *wallet_share,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UnsuspendWalletShareV0 { args },
+    settle_wallet_share_accounts(fanout, wallet_share, inflows),
+  )
+}
+
+/// `cron_job` is the token inflow's own cron job if it has one, otherwise the fanout's
+pub fn initialize_voucher_v0(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  fanout_state: &FanoutV0,
+  cron_job: &Pubkey,
+  wallet_share: &Pubkey,
+  mint: &Pubkey,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::InitializeVoucherV0 {
+      payer: *payer,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, mint).0,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        mint,
+        token_program,
+      ),
+      voucher: voucher_key(fanout, mint, wallet_share).0,
+      wallet_share: *wallet_share,
+      mint: *mint,
+      authority: queue_authority_key().0,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(
+        cron_job,
+        next_cron_transaction_id(fanout_state),
+      )
+      .0,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      token_program: *token_program,
+      associated_token_program: associated_token::ID,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::InitializeVoucherV0 {},
+  )
+}
+
+pub fn update_voucher_v0(
+  wallet: &Pubkey,
+  voucher: &Pubkey,
+  args: UpdateVoucherV0Args,
+) -> Instruction {
+  build(
+    accounts::UpdateVoucherV0 {
+      wallet: *wallet,
+      voucher: *voucher,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UpdateVoucherV0 { args },
+  )
+}
+
+pub fn claim_v0(
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::ClaimV0 {
+      fanout: *fanout,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      wallet: voucher_state.wallet,
+      mint: voucher_state.mint,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        &voucher_state.mint,
+        token_program,
+      ),
+      receiver_token_account: get_associated_token_address_with_program_id(
+        &voucher_state.wallet,
+        &voucher_state.mint,
+        token_program,
+      ),
+      token_program: *token_program,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      ata_payer: Some(ata_payer_key(fanout).0),
+      associated_token_program: Some(associated_token::ID),
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::ClaimV0 {},
+  )
+}
+
+/// Simulate this instruction and decode its return data as a `ClaimPreviewV0`
+pub fn preview_claim_v0(
+  fanout: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  token_program: &Pubkey,
+) -> Instruction {
+  let inflow = Inflow {
+    mint: voucher_state.mint,
+    token_program: *token_program,
+  };
+  build(
+    accounts::PreviewClaimV0 {
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      wallet: voucher_state.wallet,
+      balance_account: inflow.balance_account(fanout),
+    },
+    instruction::PreviewClaimV0 {},
+  )
+}
+
+pub fn claim_to_v0(
+  fanout: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  destination: &Pubkey,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::ClaimToV0 {
+      wallet: voucher_state.wallet,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      mint: voucher_state.mint,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        &voucher_state.mint,
+        token_program,
+      ),
+      destination: *destination,
+      token_program: *token_program,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::ClaimToV0 {},
+  )
+}
+
+/// `cron_job` is the SOL inflow's own cron job if it has one, otherwise the fanout's
+pub fn initialize_sol_voucher_v0(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  fanout_state: &FanoutV0,
+  cron_job: &Pubkey,
+  wallet_share: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::InitializeSolVoucherV0 {
+      payer: *payer,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      voucher: voucher_key(fanout, &Pubkey::default(), wallet_share).0,
+      wallet_share: *wallet_share,
+      authority: queue_authority_key().0,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(
+        cron_job,
+        next_cron_transaction_id(fanout_state),
+      )
+      .0,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::InitializeSolVoucherV0 {},
+  )
+}
+
+pub fn claim_sol_v0(fanout: &Pubkey, voucher: &Pubkey, voucher_state: &VoucherV0) -> Instruction {
+  build(
+    accounts::ClaimSolV0 {
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      wallet: voucher_state.wallet,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::ClaimSolV0 {},
+  )
+}
+
+pub fn close_sol_voucher_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+) -> Instruction {
+  build(
+    accounts::CloseSolVoucherV0 {
+      fanout: *fanout,
+      authority: *authority,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      rent_refund: voucher_state.rent_refund,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      payer: *payer,
+      system_program: system_program::ID,
+      cron_program: cron::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::CloseSolVoucherV0 {},
+  )
+}
+
+/// Accounts needed to close the cron job of an inflow that has its own, all `None` otherwise
+struct InflowCronJobAccounts {
+  cron_job: Option<Pubkey>,
+  queue_authority: Option<Pubkey>,
+  user_cron_jobs: Option<Pubkey>,
+  cron_job_name_mapping: Option<Pubkey>,
+  task_return_account_1: Option<Pubkey>,
+  task_return_account_2: Option<Pubkey>,
+  cron_program: Option<Pubkey>,
+  system_program: Option<Pubkey>,
+}
+
+fn inflow_cron_job_accounts(
+  fanout_name: &str,
+  mint: &Pubkey,
+  cron_job: Option<&Pubkey>,
+) -> InflowCronJobAccounts {
+  let queue_authority = queue_authority_key().0;
+  InflowCronJobAccounts {
+    cron_job: cron_job.copied(),
+    queue_authority: cron_job.map(|_| queue_authority),
+    user_cron_jobs: cron_job.map(|_| user_cron_jobs_key(&queue_authority).0),
+    cron_job_name_mapping: cron_job.map(|_| {
+      cron_job_name_mapping_key(
+        &queue_authority,
+        &token_inflow_cron_job_name(fanout_name, mint),
+      )
+      .0
+    }),
+    task_return_account_1: cron_job.map(|cron_job| task_return_account_1_key(cron_job).0),
+    task_return_account_2: cron_job.map(|cron_job| task_return_account_2_key(cron_job).0),
+    cron_program: cron_job.map(|_| cron::ID),
+    system_program: cron_job.map(|_| system_program::ID),
+  }
+}
+
+/// `cron_job` is the inflow's own cron job if it has one, which is closed with it
+pub fn close_sol_inflow_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  fanout_state: &FanoutV0,
+  cron_job: Option<&Pubkey>,
+  rent_refund: &Pubkey,
+) -> Instruction {
+  let cron = inflow_cron_job_accounts(&fanout_state.name, &Pubkey::default(), cron_job);
+  build(
+    accounts::CloseSolInflowV0 {
+      fanout: *fanout,
+      authority: *authority,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      rent_refund: *rent_refund,
+      cron_job: cron.cron_job,
+      queue_authority: cron.queue_authority,
+      user_cron_jobs: cron.user_cron_jobs,
+      cron_job_name_mapping: cron.cron_job_name_mapping,
+      task_return_account_1: cron.task_return_account_1,
+      task_return_account_2: cron.task_return_account_2,
+      cron_program: cron.cron_program,
+      system_program: cron.system_program,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::CloseSolInflowV0 {},
+  )
+}
+
+pub fn withdraw_pending_v0(
+  fanout: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  destination: &Pubkey,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::WithdrawPendingV0 {
+      wallet: voucher_state.wallet,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      voucher: *voucher,
+      mint: voucher_state.mint,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        &voucher_state.mint,
+        token_program,
+      ),
+      destination: *destination,
+      token_program: *token_program,
+      wallet_share: voucher_state.wallet_share,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::WithdrawPendingV0 {},
+  )
+}
+
+pub fn withdraw_pending_sol_v0(
+  fanout: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  destination: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::WithdrawPendingSolV0 {
+      wallet: voucher_state.wallet,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      voucher: *voucher,
+      destination: *destination,
+      wallet_share: voucher_state.wallet_share,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::WithdrawPendingSolV0 {},
+  )
+}
+
+/// `token_program` is ignored for SOL vouchers
+pub fn forfeit_voucher_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  token_program: &Pubkey,
+  args: ForfeitVoucherV0Args,
+) -> Instruction {
+  let inflow = Inflow {
+    mint: voucher_state.mint,
+    token_program: *token_program,
+  };
+  build(
+    accounts::ForfeitVoucherV0 {
+      authority: *authority,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      balance_account: inflow.balance_account(fanout),
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::ForfeitVoucherV0 { args },
+  )
+}
+
+/// Pays the treasury's associated token account
+pub fn claim_treasury_v0(
+  fanout: &Pubkey,
+  mint: &Pubkey,
+  treasury: &Pubkey,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::ClaimTreasuryV0 {
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, mint).0,
+      mint: *mint,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        mint,
+        token_program,
+      ),
+      treasury: *treasury,
+      treasury_token_account: get_associated_token_address_with_program_id(
+        treasury,
+        mint,
+        token_program,
+      ),
+      token_program: *token_program,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::ClaimTreasuryV0 {},
+  )
+}
+
+pub fn claim_treasury_sol_v0(fanout: &Pubkey, treasury: &Pubkey) -> Instruction {
+  build(
+    accounts::ClaimTreasurySolV0 {
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      treasury: *treasury,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::ClaimTreasurySolV0 {},
+  )
+}
+
+pub fn rotate_voucher_wallet_v0(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::RotateVoucherWalletV0 {
+      payer: *payer,
+      fanout: *fanout,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      wallet: voucher_state.wallet,
+      mint: voucher_state.mint,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        &voucher_state.mint,
+        token_program,
+      ),
+      receiver_token_account: get_associated_token_address_with_program_id(
+        &voucher_state.wallet,
+        &voucher_state.mint,
+        token_program,
+      ),
+      token_program: *token_program,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::RotateVoucherWalletV0 {},
+  )
+}
+
+pub fn rotate_sol_voucher_wallet_v0(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+) -> Instruction {
+  build(
+    accounts::RotateSolVoucherWalletV0 {
+      payer: *payer,
+      fanout: *fanout,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      wallet: voucher_state.wallet,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::RotateSolVoucherWalletV0 {},
+  )
+}
+
+/// Schedules a voucher that was unscheduled, e.g. for a cron job migration, on `cron_job`
+pub fn reschedule_voucher_v0(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::RescheduleVoucherV0 {
+      payer: *payer,
+      fanout: *fanout,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      voucher: *voucher,
+      mint: voucher_state.mint,
+      token_program: *token_program,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::RescheduleVoucherV0 {},
+  )
+}
+
+pub fn reschedule_sol_voucher_v0(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+) -> Instruction {
+  build(
+    accounts::RescheduleSolVoucherV0 {
+      payer: *payer,
+      fanout: *fanout,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      token_inflow: token_inflow_key(fanout, &Pubkey::default()).0,
+      voucher: *voucher,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::RescheduleSolVoucherV0 {},
+  )
+}
+
+/// `mint` is `Pubkey::default()` for SOL
+pub fn update_token_inflow_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  mint: &Pubkey,
+  args: UpdateTokenInflowV0Args,
+) -> Instruction {
+  build(
+    accounts::UpdateTokenInflowV0 {
+      authority: *authority,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, mint).0,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::UpdateTokenInflowV0 { args },
+  )
+}
+
+/// `cron_job_id` is the queue authority's next cron job id and `task_id` a free task on the task
+/// queue. `mint` is `Pubkey::default()` for SOL
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_token_inflow_cron_job_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  fanout_state: &FanoutV0,
+  mint: &Pubkey,
+  task_queue: &Pubkey,
+  cron_job_id: u32,
+  task_id: u16,
+  args: InitializeTokenInflowCronJobV0Args,
+) -> Instruction {
+  let queue_authority = queue_authority_key().0;
+  let cron_job = cron_job_key(&queue_authority, cron_job_id).0;
+  build(
+    accounts::InitializeTokenInflowCronJobV0 {
+      payer: *payer,
+      authority: *authority,
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, mint).0,
+      global_state: global_state_key().0,
+      task_queue: *task_queue,
+      task_queue_authority: task_queue_authority_key(task_queue, &queue_authority).0,
+      queue_authority,
+      user_cron_jobs: user_cron_jobs_key(&queue_authority).0,
+      cron_job,
+      cron_job_name_mapping: cron_job_name_mapping_key(
+        &queue_authority,
+        &token_inflow_cron_job_name(&fanout_state.name, mint),
+      )
+      .0,
+      task: task_key(task_queue, task_id).0,
+      task_return_account_1: task_return_account_1_key(&cron_job).0,
+      task_return_account_2: task_return_account_2_key(&cron_job).0,
+      system_program: system_program::ID,
+      tuktuk_program: tuktuk::ID,
+      cron_program: cron::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::InitializeTokenInflowCronJobV0 { args },
+  )
+}
+
+/// `cron_job` is the inflow's own cron job if it has one, which is closed with it
+pub fn close_token_inflow_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  fanout_state: &FanoutV0,
+  cron_job: Option<&Pubkey>,
+  mint: &Pubkey,
+  rent_refund: &Pubkey,
+  token_program: &Pubkey,
+) -> Instruction {
+  let cron = inflow_cron_job_accounts(&fanout_state.name, mint, cron_job);
+  build(
+    accounts::CloseTokenInflowV0 {
+      fanout: *fanout,
+      authority: *authority,
+      token_inflow: token_inflow_key(fanout, mint).0,
+      mint: *mint,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        mint,
+        token_program,
+      ),
+      authority_token_account: get_associated_token_address_with_program_id(
+        authority,
+        mint,
+        token_program,
+      ),
+      rent_refund: *rent_refund,
+      token_program: *token_program,
+      associated_token_program: associated_token::ID,
+      system_program: system_program::ID,
+      cron_job: cron.cron_job,
+      queue_authority: cron.queue_authority,
+      user_cron_jobs: cron.user_cron_jobs,
+      cron_job_name_mapping: cron.cron_job_name_mapping,
+      task_return_account_1: cron.task_return_account_1,
+      task_return_account_2: cron.task_return_account_2,
+      cron_program: cron.cron_program,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::CloseTokenInflowV0 {},
+  )
+}
+
+pub fn close_wallet_share_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  wallet_share: &Pubkey,
+  rent_refund: &Pubkey,
+  inflows: &[Inflow],
+) -> Instruction {
+  build_with_remaining(
+    accounts::CloseWalletShareV0 {
+      fanout: *fanout,
+      authority: *authority,
+      wallet_share: *wallet_share,
+      rent_refund: *rent_refund,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::CloseWalletShareV0 {},
+    settle_wallet_share_accounts(fanout, wallet_share, inflows),
+  )
+}
+
+pub fn close_voucher_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+  token_program: &Pubkey,
+) -> Instruction {
+  build(
+    accounts::CloseVoucherV0 {
+      fanout: *fanout,
+      authority: *authority,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      fanout_token_account: get_associated_token_address_with_program_id(
+        fanout,
+        &voucher_state.mint,
+        token_program,
+      ),
+      voucher: *voucher,
+      wallet_share: voucher_state.wallet_share,
+      rent_refund: voucher_state.rent_refund,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      payer: *payer,
+      system_program: system_program::ID,
+      cron_program: cron::ID,
+      token_program: *token_program,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::CloseVoucherV0 {},
+  )
+}
+
+pub fn close_fanout_v0(
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  fanout_state: &FanoutV0,
+) -> Instruction {
+  let queue_authority = queue_authority_key().0;
+  build(
+    accounts::CloseFanoutV0 {
+      fanout: *fanout,
+      authority: *authority,
+      cron_job: fanout_state.cron_job,
+      queue_authority,
+      user_cron_jobs: user_cron_jobs_key(&queue_authority).0,
+      cron_job_name_mapping: cron_job_name_mapping_key(&queue_authority, &fanout_state.name).0,
+      task_return_account_1: task_return_account_1_key(&fanout_state.cron_job).0,
+      task_return_account_2: task_return_account_2_key(&fanout_state.cron_job).0,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+    },
+    instruction::CloseFanoutV0 {},
+  )
+}
+
+pub fn unschedule_voucher_v0(
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  voucher: &Pubkey,
+  voucher_state: &VoucherV0,
+) -> Instruction {
+  build(
+    accounts::UnscheduleVoucherV0 {
+      global_state: global_state_key().0,
+      fanout: *fanout,
+      cron_job: *cron_job,
+      cron_job_transaction: cron_job_transaction_key(cron_job, voucher_state.cron_transaction_id).0,
+      queue_authority: queue_authority_key().0,
+      voucher: *voucher,
+      token_inflow: token_inflow_key(fanout, &voucher_state.mint).0,
+      rent_refund: voucher_state.rent_refund,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+    },
+    instruction::UnscheduleVoucherV0 {},
+  )
+}
+
+/// `task_queue` is the global state's task queue, `new_cron_job_id` the queue authority's next cron
+/// job id and `task_id` a free task on the task queue
+pub fn migrate_fanout_cron_job_v0(
+  payer: &Pubkey,
+  authority: &Pubkey,
+  fanout: &Pubkey,
+  fanout_state: &FanoutV0,
+  task_queue: &Pubkey,
+  new_cron_job_id: u32,
+  task_id: u16,
+) -> Instruction {
+  let queue_authority = queue_authority_key().0;
+  let new_cron_job = cron_job_key(&queue_authority, new_cron_job_id).0;
+  build(
+    accounts::MigrateFanoutCronJobV0 {
+      payer: *payer,
+      authority: *authority,
+      global_state: global_state_key().0,
+      fanout: *fanout,
+      cron_job: fanout_state.cron_job,
+      old_task_return_account_1: task_return_account_1_key(&fanout_state.cron_job).0,
+      old_task_return_account_2: task_return_account_2_key(&fanout_state.cron_job).0,
+      task_queue: *task_queue,
+      task_queue_authority: task_queue_authority_key(task_queue, &queue_authority).0,
+      queue_authority,
+      user_cron_jobs: user_cron_jobs_key(&queue_authority).0,
+      new_cron_job,
+      cron_job_name_mapping: cron_job_name_mapping_key(&queue_authority, &fanout_state.name).0,
+      task: task_key(task_queue, task_id).0,
+      task_return_account_1: task_return_account_1_key(&new_cron_job).0,
+      task_return_account_2: task_return_account_2_key(&new_cron_job).0,
+      system_program: system_program::ID,
+      tuktuk_program: tuktuk::ID,
+      cron_program: cron::ID,
+      event_authority: event_authority_key().0,
+      program: wallet_fanout::ID,
+    },
+    instruction::MigrateFanoutCronJobV0 {},
+  )
+}
+
+pub fn migrate_accumulator_v0(fanout: &Pubkey, mint: &Pubkey, vouchers: &[Pubkey]) -> Instruction {
+  build_with_remaining(
+    accounts::MigrateAccumulatorV0 {
+      fanout: *fanout,
+      token_inflow: token_inflow_key(fanout, mint).0,
+    },
+    instruction::MigrateAccumulatorV0 {},
+    vouchers
+      .iter()
+      .map(|voucher| AccountMeta::new(*voucher, false))
+      .collect(),
+  )
+}
+
+pub fn migrate_voucher_v0(payer: &Pubkey, voucher: &Pubkey) -> Instruction {
+  build(
+    accounts::MigrateVoucherV0 {
+      payer: *payer,
+      voucher: *voucher,
+      system_program: system_program::ID,
+    },
+    instruction::MigrateVoucherV0 {},
+  )
+}
+
+pub fn migrate_wallet_share_v0(payer: &Pubkey, wallet_share: &Pubkey) -> Instruction {
+  build(
+    accounts::MigrateWalletShareV0 {
+      payer: *payer,
+      wallet_share: *wallet_share,
+      system_program: system_program::ID,
+    },
+    instruction::MigrateWalletShareV0 {},
+  )
+}
+
+/// Pass the mint of an inflow with its own cron job to requeue that one instead of the fanout's.
+/// `mint` is `Pubkey::default()` for SOL
+pub fn requeue_fanout_task_v0(
+  payer: &Pubkey,
+  fanout: &Pubkey,
+  cron_job: &Pubkey,
+  task_queue: &Pubkey,
+  mint: Option<&Pubkey>,
+  args: RequeueFanoutTaskArgsV0,
+) -> Instruction {
+  let queue_authority = queue_authority_key().0;
+  build(
+    accounts::RequeueFanoutTaskV0 {
+      payer: *payer,
+      global_state: global_state_key().0,
+      fanout: *fanout,
+      task_queue: *task_queue,
+      task_queue_authority: task_queue_authority_key(task_queue, &queue_authority).0,
+      queue_authority,
+      cron_job: *cron_job,
+      task: task_key(task_queue, args.task_id).0,
+      task_return_account_1: task_return_account_1_key(cron_job).0,
+      task_return_account_2: task_return_account_2_key(cron_job).0,
+      tuktuk_program: tuktuk::ID,
+      cron_program: cron::ID,
+      system_program: system_program::ID,
+      event_authority: Some(event_authority_key().0),
+      program: Some(wallet_fanout::ID),
+      token_inflow: mint.map(|mint| token_inflow_key(fanout, mint).0),
+    },
+    instruction::RequeueFanoutTaskV0 { args },
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_spl::token;
+
+  use super::*;
+
+  fn pda(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+  }
+
+  fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    pda(
+      &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+      &associated_token::ID,
+    )
+  }
+
+  fn event_authority() -> Pubkey {
+    pda(&[b"__event_authority"], &wallet_fanout::ID)
+  }
+
+  fn queue_authority() -> Pubkey {
+    pda(&[b"queue_authority"], &wallet_fanout::ID)
+  }
+
+  fn voucher_state(mint: Pubkey) -> VoucherV0 {
+    VoucherV0 {
+      fanout: Pubkey::new_unique(),
+      wallet_share: Pubkey::new_unique(),
+      wallet: Pubkey::new_unique(),
+      mint,
+      cron_transaction_id: 7,
+      ..Default::default()
+    }
+  }
+
+  fn assert_builds(ix: Instruction, accounts: impl ToAccountMetas, data: impl InstructionData) {
+    assert_eq!(ix.program_id, wallet_fanout::ID);
+    assert_eq!(ix.accounts, accounts.to_account_metas(None));
+    assert_eq!(ix.data, data.data());
+  }
+
+  #[test]
+  fn claim_v0_derives_its_accounts() {
+    let mint = Pubkey::new_unique();
+    let voucher = Pubkey::new_unique();
+    let cron_job = Pubkey::new_unique();
+    let voucher_state = voucher_state(mint);
+    let fanout = voucher_state.fanout;
+
+    assert_builds(
+      claim_v0(&fanout, &cron_job, &voucher, &voucher_state, &token::ID),
+      accounts::ClaimV0 {
+        fanout,
+        cron_job,
+        cron_job_transaction: pda(
+          &[
+            b"cron_job_transaction",
+            cron_job.as_ref(),
+            &7u32.to_le_bytes(),
+          ],
+          &cron::ID,
+        ),
+        queue_authority: queue_authority(),
+        token_inflow: pda(
+          &[b"token_inflow", fanout.as_ref(), mint.as_ref()],
+          &wallet_fanout::ID,
+        ),
+        voucher,
+        wallet_share: voucher_state.wallet_share,
+        wallet: voucher_state.wallet,
+        mint,
+        fanout_token_account: ata(&fanout, &mint, &token::ID),
+        receiver_token_account: ata(&voucher_state.wallet, &mint, &token::ID),
+        token_program: token::ID,
+        cron_program: cron::ID,
+        system_program: system_program::ID,
+        ata_payer: Some(pda(&[b"ata_payer", fanout.as_ref()], &wallet_fanout::ID)),
+        associated_token_program: Some(associated_token::ID),
+        event_authority: Some(event_authority()),
+        program: Some(wallet_fanout::ID),
+      },
+      instruction::ClaimV0 {},
+    );
+  }
+
+  #[test]
+  fn initialize_voucher_v0_takes_the_next_cron_transaction_id() {
+    let payer = Pubkey::new_unique();
+    let fanout = Pubkey::new_unique();
+    let cron_job = Pubkey::new_unique();
+    let wallet_share = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    // Returned ids are reused, most recently returned first
+    let fanout_state = FanoutV0 {
+      available_cron_transaction_ids: vec![3, 9],
+      next_cron_transaction_id: 12,
+      ..Default::default()
+    };
+
+    assert_builds(
+      initialize_voucher_v0(
+        &payer,
+        &fanout,
+        &fanout_state,
+        &cron_job,
+        &wallet_share,
+        &mint,
+        &token::ID,
+      ),
+      accounts::InitializeVoucherV0 {
+        payer,
+        fanout,
+        token_inflow: pda(
+          &[b"token_inflow", fanout.as_ref(), mint.as_ref()],
+          &wallet_fanout::ID,
+        ),
+        fanout_token_account: ata(&fanout, &mint, &token::ID),
+        voucher: pda(
+          &[
+            b"voucher",
+            fanout.as_ref(),
+            mint.as_ref(),
+            wallet_share.as_ref(),
+          ],
+          &wallet_fanout::ID,
+        ),
+        wallet_share,
+        mint,
+        authority: queue_authority(),
+        cron_job,
+        cron_job_transaction: pda(
+          &[
+            b"cron_job_transaction",
+            cron_job.as_ref(),
+            &9u32.to_le_bytes(),
+          ],
+          &cron::ID,
+        ),
+        cron_program: cron::ID,
+        system_program: system_program::ID,
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+        event_authority: Some(event_authority()),
+        program: Some(wallet_fanout::ID),
+      },
+      instruction::InitializeVoucherV0 {},
+    );
+  }
+
+  #[test]
+  fn withdraw_pending_sol_v0_uses_the_sol_inflow() {
+    let voucher = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let voucher_state = voucher_state(Pubkey::default());
+    let fanout = voucher_state.fanout;
+
+    assert_builds(
+      withdraw_pending_sol_v0(&fanout, &voucher, &voucher_state, &destination),
+      accounts::WithdrawPendingSolV0 {
+        wallet: voucher_state.wallet,
+        fanout,
+        token_inflow: pda(
+          &[b"token_inflow", fanout.as_ref(), &[0; 32]],
+          &wallet_fanout::ID,
+        ),
+        voucher,
+        destination,
+        wallet_share: voucher_state.wallet_share,
+        event_authority: event_authority(),
+        program: wallet_fanout::ID,
+      },
+      instruction::WithdrawPendingSolV0 {},
+    );
+  }
+
+  #[test]
+  fn update_global_state_v0_only_passes_a_new_task_queue() {
+    let authority = Pubkey::new_unique();
+    let task_queue = Pubkey::new_unique();
+    let expected = |task_queue: Option<Pubkey>| accounts::UpdateGlobalStateV0 {
+      authority,
+      global_state: pda(&[b"global_state"], &wallet_fanout::ID),
+      queue_authority: queue_authority(),
+      task_queue,
+      task_queue_authority: task_queue.map(|task_queue| {
+        pda(
+          &[
+            b"task_queue_authority",
+            task_queue.as_ref(),
+            queue_authority().as_ref(),
+          ],
+          &tuktuk::ID,
+        )
+      }),
+      event_authority: event_authority(),
+      program: wallet_fanout::ID,
+    };
+    let args = || UpdateGlobalStateV0Args {
+      pending_authority: None,
+    };
+
+    assert_builds(
+      update_global_state_v0(&authority, None, args()),
+      expected(None),
+      instruction::UpdateGlobalStateV0 { args: args() },
+    );
+    assert_builds(
+      update_global_state_v0(&authority, Some(&task_queue), args()),
+      expected(Some(task_queue)),
+      instruction::UpdateGlobalStateV0 { args: args() },
+    );
+  }
+
+  #[test]
+  fn close_wallet_share_v0_settles_every_inflow() {
+    let authority = Pubkey::new_unique();
+    let fanout = Pubkey::new_unique();
+    let wallet_share = Pubkey::new_unique();
+    let rent_refund = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let inflows = [
+      Inflow::sol(),
+      Inflow {
+        mint,
+        token_program: token::ID,
+      },
+    ];
+
+    let ix = close_wallet_share_v0(&authority, &fanout, &wallet_share, &rent_refund, &inflows);
+
+    let mut expected = accounts::CloseWalletShareV0 {
+      fanout,
+      authority,
+      wallet_share,
+      rent_refund,
+      event_authority: Some(event_authority()),
+      program: Some(wallet_fanout::ID),
+    }
+    .to_account_metas(None);
+    for (mint, balance_account) in [
+      (Pubkey::default(), fanout),
+      (mint, ata(&fanout, &mint, &token::ID)),
+    ] {
+      expected.extend([
+        AccountMeta::new(
+          pda(
+            &[b"token_inflow", fanout.as_ref(), mint.as_ref()],
+            &wallet_fanout::ID,
+          ),
+          false,
+        ),
+        AccountMeta::new_readonly(balance_account, false),
+        AccountMeta::new(
+          pda(
+            &[
+              b"voucher",
+              fanout.as_ref(),
+              mint.as_ref(),
+              wallet_share.as_ref(),
+            ],
+            &wallet_fanout::ID,
+          ),
+          false,
+        ),
+      ]);
+    }
+    assert_eq!(ix.accounts, expected);
+    assert_eq!(ix.data, instruction::CloseWalletShareV0 {}.data());
+  }
+}
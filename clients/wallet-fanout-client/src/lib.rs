@@ -0,0 +1,8 @@
+//! Rust client for the wallet-fanout program: PDA helpers, instruction builders and account
+//! fetching.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pdas;
+
+pub use wallet_fanout::{self, events, state::*, ID};
@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use tuktuk_program::{cron, tuktuk};
+use wallet_fanout::hash_name;
+
+pub fn global_state_key() -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"global_state"], &wallet_fanout::ID)
+}
+
+pub fn fanout_key(name: &str) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"fanout", &hash_name(name)], &wallet_fanout::ID)
+}
+
+pub fn queue_authority_key() -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"queue_authority"], &wallet_fanout::ID)
+}
+
+pub fn wallet_share_key(fanout: &Pubkey, index: u32) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[b"wallet_share", fanout.as_ref(), &index.to_le_bytes()],
+    &wallet_fanout::ID,
+  )
+}
+
+/// Use `Pubkey::default()` as the mint for the SOL inflow
+pub fn token_inflow_key(fanout: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[b"token_inflow", fanout.as_ref(), mint.as_ref()],
+    &wallet_fanout::ID,
+  )
+}
+
+/// Use `Pubkey::default()` as the mint for SOL vouchers
+pub fn voucher_key(fanout: &Pubkey, mint: &Pubkey, wallet_share: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      b"voucher",
+      fanout.as_ref(),
+      mint.as_ref(),
+      wallet_share.as_ref(),
+    ],
+    &wallet_fanout::ID,
+  )
+}
+
+pub fn ata_payer_key(fanout: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"ata_payer", fanout.as_ref()], &wallet_fanout::ID)
+}
+
+pub fn event_authority_key() -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"__event_authority"], &wallet_fanout::ID)
+}
+
+pub fn task_queue_authority_key(task_queue: &Pubkey, queue_authority: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      b"task_queue_authority",
+      task_queue.as_ref(),
+      queue_authority.as_ref(),
+    ],
+    &tuktuk::ID,
+  )
+}
+
+pub fn task_key(task_queue: &Pubkey, task_id: u16) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[b"task", task_queue.as_ref(), &task_id.to_le_bytes()],
+    &tuktuk::ID,
+  )
+}
+
+pub fn user_cron_jobs_key(authority: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"user_cron_jobs", authority.as_ref()], &cron::ID)
+}
+
+pub fn cron_job_key(authority: &Pubkey, cron_job_id: u32) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[b"cron_job", authority.as_ref(), &cron_job_id.to_le_bytes()],
+    &cron::ID,
+  )
+}
+
+pub fn cron_job_name_mapping_key(authority: &Pubkey, name: &str) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      b"cron_job_name_mapping",
+      authority.as_ref(),
+      &hash_name(name),
+    ],
+    &cron::ID,
+  )
+}
+
+pub fn cron_job_transaction_key(cron_job: &Pubkey, cron_transaction_id: u32) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      b"cron_job_transaction",
+      cron_job.as_ref(),
+      &cron_transaction_id.to_le_bytes(),
+    ],
+    &cron::ID,
+  )
+}
+
+pub fn task_return_account_1_key(cron_job: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"task_return_account_1", cron_job.as_ref()], &cron::ID)
+}
+
+pub fn task_return_account_2_key(cron_job: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[b"task_return_account_2", cron_job.as_ref()], &cron::ID)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pda(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+  }
+
+  #[test]
+  fn fanout_key_hashes_the_name() {
+    // sha256("test")
+    let name_hash = [
+      0x9f, 0x86, 0xd0, 0x81, 0x88, 0x4c, 0x7d, 0x65, 0x9a, 0x2f, 0xea, 0xa0, 0xc5, 0x5a, 0xd0,
+      0x15, 0xa3, 0xbf, 0x4f, 0x1b, 0x2b, 0x0b, 0x82, 0x2c, 0xd1, 0x5d, 0x6c, 0x15, 0xb0, 0xf0,
+      0x0a, 0x08,
+    ];
+    assert_eq!(
+      fanout_key("test").0,
+      pda(&[b"fanout", &name_hash], &wallet_fanout::ID)
+    );
+    assert_eq!(
+      cron_job_name_mapping_key(&queue_authority_key().0, "test").0,
+      pda(
+        &[
+          b"cron_job_name_mapping",
+          queue_authority_key().0.as_ref(),
+          &name_hash
+        ],
+        &cron::ID
+      )
+    );
+  }
+
+  #[test]
+  fn program_keys_match_their_seeds() {
+    let fanout = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let wallet_share = Pubkey::new_unique();
+
+    assert_eq!(
+      global_state_key().0,
+      pda(&[b"global_state"], &wallet_fanout::ID)
+    );
+    assert_eq!(
+      queue_authority_key().0,
+      pda(&[b"queue_authority"], &wallet_fanout::ID)
+    );
+    assert_eq!(
+      event_authority_key().0,
+      pda(&[b"__event_authority"], &wallet_fanout::ID)
+    );
+    assert_eq!(
+      ata_payer_key(&fanout).0,
+      pda(&[b"ata_payer", fanout.as_ref()], &wallet_fanout::ID)
+    );
+    // Indexes are little endian
+    assert_eq!(
+      wallet_share_key(&fanout, 258).0,
+      pda(
+        &[b"wallet_share", fanout.as_ref(), &[2, 1, 0, 0]],
+        &wallet_fanout::ID
+      )
+    );
+    assert_eq!(
+      token_inflow_key(&fanout, &mint).0,
+      pda(
+        &[b"token_inflow", fanout.as_ref(), mint.as_ref()],
+        &wallet_fanout::ID
+      )
+    );
+    assert_eq!(
+      voucher_key(&fanout, &mint, &wallet_share).0,
+      pda(
+        &[
+          b"voucher",
+          fanout.as_ref(),
+          mint.as_ref(),
+          wallet_share.as_ref()
+        ],
+        &wallet_fanout::ID
+      )
+    );
+  }
+
+  #[test]
+  fn sol_keys_use_the_default_pubkey_as_mint() {
+    let fanout = Pubkey::new_unique();
+    let wallet_share = Pubkey::new_unique();
+
+    assert_eq!(
+      token_inflow_key(&fanout, &Pubkey::default()).0,
+      pda(
+        &[b"token_inflow", fanout.as_ref(), &[0; 32]],
+        &wallet_fanout::ID
+      )
+    );
+    assert_eq!(
+      voucher_key(&fanout, &Pubkey::default(), &wallet_share).0,
+      pda(
+        &[b"voucher", fanout.as_ref(), &[0; 32], wallet_share.as_ref()],
+        &wallet_fanout::ID
+      )
+    );
+  }
+
+  #[test]
+  fn tuktuk_keys_match_their_seeds() {
+    let task_queue = Pubkey::new_unique();
+    let cron_job = Pubkey::new_unique();
+    let queue_authority = queue_authority_key().0;
+
+    assert_eq!(
+      task_queue_authority_key(&task_queue, &queue_authority).0,
+      pda(
+        &[
+          b"task_queue_authority",
+          task_queue.as_ref(),
+          queue_authority.as_ref()
+        ],
+        &tuktuk::ID
+      )
+    );
+    assert_eq!(
+      task_key(&task_queue, 258).0,
+      pda(&[b"task", task_queue.as_ref(), &[2, 1]], &tuktuk::ID)
+    );
+    assert_eq!(
+      user_cron_jobs_key(&queue_authority).0,
+      pda(&[b"user_cron_jobs", queue_authority.as_ref()], &cron::ID)
+    );
+    assert_eq!(
+      cron_job_key(&queue_authority, 258).0,
+      pda(
+        &[b"cron_job", queue_authority.as_ref(), &[2, 1, 0, 0]],
+        &cron::ID
+      )
+    );
+    assert_eq!(
+      cron_job_transaction_key(&cron_job, 258).0,
+      pda(
+        &[b"cron_job_transaction", cron_job.as_ref(), &[2, 1, 0, 0]],
+        &cron::ID
+      )
+    );
+    assert_eq!(
+      task_return_account_1_key(&cron_job).0,
+      pda(&[b"task_return_account_1", cron_job.as_ref()], &cron::ID)
+    );
+    assert_eq!(
+      task_return_account_2_key(&cron_job).0,
+      pda(&[b"task_return_account_2", cron_job.as_ref()], &cron::ID)
+    );
+  }
+}
@@ -71,4 +71,12 @@ export function voucherKey(
   );
 }
 
-
+export function ataPayerKey(
+  fanout: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("ata_payer", "utf-8"), fanout.toBuffer()],
+    programId
+  );
+}
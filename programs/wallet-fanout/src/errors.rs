@@ -12,4 +12,44 @@ pub enum ErrorCode {
   CannotCloseFanoutWithInflows,
   #[msg("Total shares issued exceeded total shares")]
   TotalSharesExceeded,
+  #[msg("Voucher has a pending amount that has not been paid out")]
+  PendingAmountNotPaid,
+  #[msg("No pending amount to withdraw")]
+  NoPendingAmount,
+  #[msg("Signer is not the pending authority")]
+  InvalidPendingAuthority,
+  #[msg("Task queue authority is missing or does not belong to the queue authority")]
+  InvalidTaskQueueAuthority,
+  #[msg("Fanout cron job does not need to be migrated")]
+  CronJobUpToDate,
+  #[msg("Cron job still has transactions")]
+  CronJobHasTransactions,
+  #[msg("Every token inflow of the fanout must be passed with its balance account")]
+  InvalidInflowAccounts,
+  #[msg("Treasury does not match the fanout's unissued share policy")]
+  InvalidTreasury,
+  #[msg("Nothing is owed to the treasury")]
+  NoTreasuryAmount,
+  #[msg("Voucher already fits the current layout")]
+  VoucherUpToDate,
+  #[msg("Payouts are paused")]
+  FanoutPaused,
+  #[msg("Wallet share is suspended")]
+  WalletShareSuspended,
+  #[msg("Wallet share is not suspended")]
+  WalletShareNotSuspended,
+  #[msg("Wallet share already fits the current layout")]
+  WalletShareUpToDate,
+  #[msg("Voucher has no pending amount that can expire")]
+  NothingToForfeit,
+  #[msg("Claim window has not elapsed")]
+  ClaimWindowNotElapsed,
+  #[msg("Cron job does not run this inflow's claims")]
+  InvalidCronJob,
+  #[msg("Token inflow already has its own cron job")]
+  InflowHasCronJob,
+  #[msg("Treasury must be paid before the inflow is closed")]
+  TreasuryAmountOwed,
+  #[msg("Voucher is already scheduled")]
+  VoucherScheduled,
 }
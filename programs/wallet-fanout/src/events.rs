@@ -0,0 +1,366 @@
+use anchor_lang::{
+  prelude::*,
+  solana_program::{instruction::Instruction, program::invoke_signed},
+  Event,
+};
+
+use crate::state::{
+  ForfeitDestination, TokenInflowV0, TransferFeePolicy, UnissuedSharePolicy, VoucherV0,
+};
+
+#[event]
+pub struct FanoutInitializedV0 {
+  pub fanout: Pubkey,
+  pub authority: Pubkey,
+  pub name: String,
+  pub total_shares: u32,
+  pub cron_job: Pubkey,
+}
+
+#[event]
+pub struct FanoutClosedV0 {
+  pub fanout: Pubkey,
+}
+
+#[event]
+pub struct TotalSharesUpdatedV0 {
+  pub fanout: Pubkey,
+  pub old_total_shares: u32,
+  pub new_total_shares: u32,
+}
+
+/// Emitted when a wallet share is created, changed or closed. A closed share has `new_shares` of 0
+#[event]
+pub struct WalletShareUpdatedV0 {
+  pub fanout: Pubkey,
+  pub wallet_share: Pubkey,
+  pub wallet: Pubkey,
+  pub old_shares: u32,
+  pub new_shares: u32,
+  pub total_shares_issued: u32,
+}
+
+#[event]
+pub struct VoucherInitializedV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub wallet_share: Pubkey,
+  pub wallet: Pubkey,
+  /// `Pubkey::default()` for SOL
+  pub mint: Pubkey,
+  pub shares: u32,
+  pub cron_transaction_id: u32,
+}
+
+#[event]
+pub struct VoucherClosedV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub wallet_share: Pubkey,
+  pub mint: Pubkey,
+}
+
+#[event]
+pub struct VoucherClaimedV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub wallet: Pubkey,
+  pub mint: Pubkey,
+  /// Where the payout went, or `None` if it was escrowed in the voucher's pending amount
+  pub destination: Option<Pubkey>,
+  /// Newly owed amount since the last claim
+  pub amount: u64,
+  /// Previously pending amount paid out along with it
+  pub pending_paid: u64,
+  /// Amount owed below one token, carried over to the next claim. Scaled by `TWELVE_PREC`
+  pub dust: u128,
+  pub total_inflow: u64,
+  pub acc_per_share: u128,
+}
+
+impl VoucherClaimedV0 {
+  pub fn new(
+    voucher: &Account<VoucherV0>,
+    token_inflow: &TokenInflowV0,
+    destination: Option<Pubkey>,
+    amount: u64,
+    pending_paid: u64,
+  ) -> Self {
+    Self {
+      fanout: voucher.fanout,
+      voucher: voucher.key(),
+      wallet: voucher.wallet,
+      mint: voucher.mint,
+      destination,
+      amount,
+      pending_paid,
+      dust: voucher.dust(token_inflow),
+      total_inflow: token_inflow.total_inflow,
+      acc_per_share: token_inflow.acc_per_share,
+    }
+  }
+}
+
+/// A claim for a suspended wallet share, held back in the voucher's `escrowed_amount` instead of paid
+#[event]
+pub struct VoucherClaimWithheldV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub wallet: Pubkey,
+  pub mint: Pubkey,
+  /// Newly owed amount since the last claim
+  pub amount: u64,
+  pub escrowed_amount: u64,
+}
+
+impl VoucherClaimWithheldV0 {
+  pub fn new(voucher: &Account<VoucherV0>, amount: u64) -> Self {
+    Self {
+      fanout: voucher.fanout,
+      voucher: voucher.key(),
+      wallet: voucher.wallet,
+      mint: voucher.mint,
+      amount,
+      escrowed_amount: voucher.escrowed_amount,
+    }
+  }
+}
+
+/// Whatever the old wallet couldn't be paid moves to the new wallet with the voucher
+#[event]
+pub struct VoucherWalletRotatedV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub mint: Pubkey,
+  pub old_wallet: Pubkey,
+  pub new_wallet: Pubkey,
+  /// Pending amount carried over to the new wallet
+  pub pending_amount: u64,
+  /// Amount held back while suspended, carried over to the new wallet
+  pub escrowed_amount: u64,
+}
+
+impl VoucherWalletRotatedV0 {
+  pub fn new(voucher: &Account<VoucherV0>, old_wallet: Pubkey) -> Self {
+    Self {
+      fanout: voucher.fanout,
+      voucher: voucher.key(),
+      mint: voucher.mint,
+      old_wallet,
+      new_wallet: voucher.wallet,
+      pending_amount: voucher.pending_amount,
+      escrowed_amount: voucher.escrowed_amount,
+    }
+  }
+}
+
+#[event]
+pub struct TokenInflowClosedV0 {
+  pub fanout: Pubkey,
+  pub token_inflow: Pubkey,
+  pub mint: Pubkey,
+}
+
+#[event]
+pub struct WalletShareSuspendedV0 {
+  pub fanout: Pubkey,
+  pub wallet_share: Pubkey,
+  pub wallet: Pubkey,
+}
+
+#[event]
+pub struct WalletShareUnsuspendedV0 {
+  pub fanout: Pubkey,
+  pub wallet_share: Pubkey,
+  pub wallet: Pubkey,
+  pub forfeited: bool,
+}
+
+/// Emitted per voucher when a wallet share is unsuspended, with the amount that was held back
+#[event]
+pub struct EscrowReleasedV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub mint: Pubkey,
+  pub amount: u64,
+  /// Whether the amount went back to the pool rather than to the member
+  pub forfeited: bool,
+}
+
+/// `mint` is set when only that inflow was paused
+#[event]
+pub struct FanoutPausedV0 {
+  pub fanout: Pubkey,
+  pub mint: Option<Pubkey>,
+}
+
+/// `mint` is set when only that inflow was resumed
+#[event]
+pub struct FanoutResumedV0 {
+  pub fanout: Pubkey,
+  pub mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct TokenInflowCronJobInitializedV0 {
+  pub fanout: Pubkey,
+  pub token_inflow: Pubkey,
+  pub mint: Pubkey,
+  pub cron_job: Pubkey,
+  pub schedule: String,
+}
+
+#[event]
+pub struct FanoutTaskRequeuedV0 {
+  pub fanout: Pubkey,
+  pub cron_job: Pubkey,
+  pub task_id: u16,
+}
+
+#[event]
+pub struct VoucherForfeitedV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub wallet_share: Pubkey,
+  pub mint: Pubkey,
+  pub amount: u64,
+  pub destination: ForfeitDestination,
+}
+
+/// Emitted when a wallet withdraws its voucher's pending amount to a destination of its choosing
+#[event]
+pub struct PendingWithdrawnV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub wallet: Pubkey,
+  /// `Pubkey::default()` for SOL
+  pub mint: Pubkey,
+  pub destination: Pubkey,
+  pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryClaimedV0 {
+  pub fanout: Pubkey,
+  pub token_inflow: Pubkey,
+  /// `Pubkey::default()` for SOL
+  pub mint: Pubkey,
+  pub treasury: Pubkey,
+  pub amount: u64,
+}
+
+/// A `pending_authority` of `None` cancels the pending proposal
+#[event]
+pub struct FanoutAuthorityProposedV0 {
+  pub fanout: Pubkey,
+  pub authority: Pubkey,
+  pub pending_authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct FanoutAuthorityAcceptedV0 {
+  pub fanout: Pubkey,
+  pub old_authority: Pubkey,
+  pub new_authority: Pubkey,
+}
+
+/// Carries the global state's settings after the update
+#[event]
+pub struct GlobalStateUpdatedV0 {
+  pub global_state: Pubkey,
+  pub pending_authority: Option<Pubkey>,
+  pub task_queue: Pubkey,
+}
+
+#[event]
+pub struct GlobalStateAuthorityAcceptedV0 {
+  pub global_state: Pubkey,
+  pub old_authority: Pubkey,
+  pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct UnissuedSharePolicyUpdatedV0 {
+  pub fanout: Pubkey,
+  pub old_unissued_share_policy: UnissuedSharePolicy,
+  pub new_unissued_share_policy: UnissuedSharePolicy,
+}
+
+/// Carries the fanout's settings after the update
+#[event]
+pub struct FanoutUpdatedV0 {
+  pub fanout: Pubkey,
+  pub transfer_fee_policy: TransferFeePolicy,
+  pub claim_window_secs: Option<u64>,
+}
+
+/// The schedule only takes effect once the cron job is migrated
+#[event]
+pub struct FanoutScheduleUpdatedV0 {
+  pub fanout: Pubkey,
+  pub pending_schedule: String,
+}
+
+#[event]
+pub struct FanoutCronJobMigratedV0 {
+  pub fanout: Pubkey,
+  pub old_cron_job: Pubkey,
+  pub new_cron_job: Pubkey,
+  pub task_queue: Pubkey,
+  pub schedule: String,
+  /// Lamports moved from the old cron job to fund the new one
+  pub funding: u64,
+}
+
+#[event]
+pub struct TokenInflowUpdatedV0 {
+  pub fanout: Pubkey,
+  pub token_inflow: Pubkey,
+  pub mint: Pubkey,
+  pub min_payout: u64,
+}
+
+#[event]
+pub struct VoucherUpdatedV0 {
+  pub fanout: Pubkey,
+  pub voucher: Pubkey,
+  pub wallet: Pubkey,
+  pub mint: Pubkey,
+  pub min_payout: u64,
+}
+
+/// The PDA `emit_cpi!` signs with, for compiling cron transactions from instructions that may not
+/// be passed it
+pub fn event_authority_key() -> Pubkey {
+  Pubkey::find_program_address(&[b"__event_authority"], &crate::ID).0
+}
+
+/// Same as `emit_cpi!`, for instructions compiled into cron transactions before events existed. Those
+/// don't pass the event authority, so the event is logged instead.
+pub fn emit_optional_cpi<E: Event>(
+  event_authority: Option<&AccountInfo>,
+  event_authority_bump: Option<u8>,
+  event: E,
+) -> Result<()> {
+  let (Some(event_authority), Some(bump)) = (event_authority, event_authority_bump) else {
+    emit!(event);
+    return Ok(());
+  };
+
+  let ix_data: Vec<u8> = anchor_lang::event::EVENT_IX_TAG_LE
+    .iter()
+    .copied()
+    .chain(event.data())
+    .collect();
+  let ix = Instruction::new_with_bytes(
+    crate::ID,
+    &ix_data,
+    vec![AccountMeta::new_readonly(event_authority.key(), true)],
+  );
+  invoke_signed(
+    &ix,
+    std::slice::from_ref(event_authority),
+    &[&[b"__event_authority", &[bump]]],
+  )
+  .map_err(Into::into)
+}
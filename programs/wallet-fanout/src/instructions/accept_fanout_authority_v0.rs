@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorCode, events::FanoutAuthorityAcceptedV0, state::FanoutV0};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptFanoutAuthorityV0<'info> {
+  pub pending_authority: Signer<'info>,
+  #[account(
+    mut,
+    constraint = fanout.pending_authority == Some(pending_authority.key()) @ ErrorCode::InvalidPendingAuthority,
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+}
+
+pub fn handler(ctx: Context<AcceptFanoutAuthorityV0>) -> Result<()> {
+  let fanout = &mut ctx.accounts.fanout;
+  let old_authority = fanout.authority;
+  fanout.authority = ctx.accounts.pending_authority.key();
+  fanout.pending_authority = None;
+
+  emit_cpi!(FanoutAuthorityAcceptedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    old_authority,
+    new_authority: ctx.accounts.pending_authority.key(),
+  });
+
+  Ok(())
+}
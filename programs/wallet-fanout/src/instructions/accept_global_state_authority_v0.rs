@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorCode, events::GlobalStateAuthorityAcceptedV0, state::GlobalStateV0};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptGlobalStateAuthorityV0<'info> {
+  pub pending_authority: Signer<'info>,
+  #[account(
+    mut,
+    seeds = [b"global_state"],
+    bump = global_state.bump,
+    constraint = global_state.pending_authority == Some(pending_authority.key()) @ ErrorCode::InvalidPendingAuthority,
+  )]
+  pub global_state: Box<Account<'info, GlobalStateV0>>,
+}
+
+pub fn handler(ctx: Context<AcceptGlobalStateAuthorityV0>) -> Result<()> {
+  let global_state = &mut ctx.accounts.global_state;
+  let old_authority = global_state.authority;
+  global_state.authority = ctx.accounts.pending_authority.key();
+  global_state.pending_authority = None;
+
+  emit_cpi!(GlobalStateAuthorityAcceptedV0 {
+    global_state: ctx.accounts.global_state.key(),
+    old_authority,
+    new_authority: ctx.accounts.pending_authority.key(),
+  });
+
+  Ok(())
+}
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  events::{emit_optional_cpi, VoucherClaimWithheldV0, VoucherClaimedV0},
+  program::WalletFanout,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+/// The lamports held by the fanout above its rent exempt minimum
+pub fn distributable_lamports(fanout: &AccountInfo) -> Result<u64> {
+  let rent = Rent::get()?;
+  Ok(
+    fanout
+      .lamports()
+      .saturating_sub(rent.minimum_balance(fanout.data_len())),
+  )
+}
+
+#[derive(Accounts)]
+pub struct ClaimSolV0<'info> {
+  #[account(mut)]
+  pub fanout: Account<'info, FanoutV0>,
+
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), Pubkey::default().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        mut,
+        has_one = fanout,
+        has_one = wallet,
+        has_one = wallet_share,
+        constraint = voucher.mint == token_inflow.mint,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+  /// CHECK: Loaded with WalletShareV0::load, which also reads wallet shares that aren't migrated
+  #[account(owner = crate::ID)]
+  pub wallet_share: AccountInfo<'info>,
+  /// CHECK: Checked by has_one
+  #[account(mut)]
+  pub wallet: AccountInfo<'info>,
+  // Optional so that cron transactions compiled before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
+}
+
+/// Whether `destination` can take `amount` lamports. The runtime rejects a transfer that leaves an
+/// account funded but below its rent exempt minimum, so small payouts to empty wallets are escrowed
+pub fn can_receive_lamports(destination: &AccountInfo, amount: u64) -> Result<bool> {
+  let rent = Rent::get()?;
+  Ok(rent.is_exempt(
+    destination.lamports().checked_add(amount).unwrap(),
+    destination.data_len(),
+  ))
+}
+
+/// Pays everything owed to `voucher` in lamports to `wallet`, keeping the inflow snapshot in step
+/// with the fanout's distributable lamports. When `wallet` can't take the payout it is added to the
+/// voucher's `pending_amount` until it adds up to enough
+pub fn claim_lamports<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  token_inflow: &mut Account<'info, TokenInflowV0>,
+  voucher: &mut Account<'info, VoucherV0>,
+  wallet: &AccountInfo<'info>,
+) -> Result<VoucherClaimedV0> {
+  let balance = distributable_lamports(&fanout.to_account_info())?;
+  token_inflow.update_total_inflow(balance, fanout);
+  let dist_amount = voucher.calculate_claim(token_inflow);
+  if dist_amount > 0 {
+    voucher.last_claimed_inflow = token_inflow.total_inflow;
+  }
+
+  let pending_paid = voucher.pending_amount;
+  let amount = dist_amount.checked_add(pending_paid).unwrap();
+  if !can_receive_lamports(wallet, amount)? {
+    msg!("Wallet can't receive, escrowing {}", dist_amount);
+    voucher.add_pending(dist_amount, Clock::get()?.unix_timestamp);
+    return Ok(VoucherClaimedV0::new(
+      voucher,
+      token_inflow,
+      None,
+      dist_amount,
+      0,
+    ));
+  }
+  if amount > 0 {
+    pay_lamports(fanout, token_inflow, voucher, wallet, amount)?;
+    voucher.pending_amount = 0;
+  }
+
+  Ok(VoucherClaimedV0::new(
+    voucher,
+    token_inflow,
+    Some(wallet.key()),
+    dist_amount,
+    pending_paid,
+  ))
+}
+
+/// Sends `amount` owed to `voucher` from the fanout's lamports to `destination`. Expects
+/// `token_inflow` to already be updated to the current balance.
+pub fn pay_lamports<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  token_inflow: &mut Account<'info, TokenInflowV0>,
+  voucher: &mut Account<'info, VoucherV0>,
+  destination: &AccountInfo<'info>,
+  amount: u64,
+) -> Result<()> {
+  // The fanout is owned by this program, so lamports can be moved directly
+  fanout.sub_lamports(amount)?;
+  destination.add_lamports(amount)?;
+
+  voucher.total_gross_claimed = voucher.total_gross_claimed.checked_add(amount).unwrap();
+  voucher.total_net_claimed = voucher.total_net_claimed.checked_add(amount).unwrap();
+  voucher.record_claim(amount, &Clock::get()?);
+  token_inflow.last_snapshot_amount = distributable_lamports(&fanout.to_account_info())?;
+
+  Ok(())
+}
+
+pub fn handler(ctx: Context<ClaimSolV0>) -> Result<()> {
+  let wallet_share = WalletShareV0::load(&ctx.accounts.wallet_share)?;
+
+  // The cron job keeps firing while paused, so skip the payout and let inflow keep accruing
+  if ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout) {
+    msg!("Payouts are paused, skipping claim");
+    return Ok(());
+  }
+
+  let balance = distributable_lamports(&ctx.accounts.fanout.to_account_info())?;
+  ctx
+    .accounts
+    .token_inflow
+    .update_total_inflow(balance, &ctx.accounts.fanout);
+
+  // Suspended members keep accruing, held back on the voucher until unsuspended
+  if wallet_share.suspended {
+    let voucher = &mut ctx.accounts.voucher;
+    let amount = voucher.withhold_claim(&ctx.accounts.token_inflow);
+    voucher.set_shares(wallet_share.shares, &ctx.accounts.token_inflow);
+    emit_optional_cpi(
+      ctx.accounts.event_authority.as_ref(),
+      ctx.bumps.event_authority,
+      VoucherClaimWithheldV0::new(voucher, amount),
+    )?;
+    return Ok(());
+  }
+
+  if ctx
+    .accounts
+    .voucher
+    .below_min_payout(&ctx.accounts.token_inflow)
+  {
+    msg!("Owed amount is below the minimum payout, skipping claim");
+    return Ok(());
+  }
+
+  let event = claim_lamports(
+    &ctx.accounts.fanout,
+    &mut ctx.accounts.token_inflow,
+    &mut ctx.accounts.voucher,
+    &ctx.accounts.wallet,
+  )?;
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    event,
+  )?;
+
+  ctx
+    .accounts
+    .voucher
+    .set_shares(wallet_share.shares, &ctx.accounts.token_inflow);
+
+  Ok(())
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::claim_tokens;
+use crate::{
+  errors::ErrorCode,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimToV0<'info> {
+  pub wallet: Signer<'info>,
+  pub fanout: Account<'info, FanoutV0>,
+
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), mint.key().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        mut,
+        has_one = fanout,
+        has_one = mint,
+        has_one = wallet,
+        has_one = wallet_share,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+  /// CHECK: Loaded with WalletShareV0::load, which also reads wallet shares that aren't migrated
+  #[account(owner = crate::ID)]
+  pub wallet_share: AccountInfo<'info>,
+
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fanout,
+        associated_token::token_program = token_program,
+    )]
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+  pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ClaimToV0>) -> Result<()> {
+  let wallet_share = WalletShareV0::load(&ctx.accounts.wallet_share)?;
+
+  require!(
+    !ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout),
+    ErrorCode::FanoutPaused
+  );
+  require!(!wallet_share.suspended, ErrorCode::WalletShareSuspended);
+
+  let event = claim_tokens(
+    &ctx.accounts.fanout,
+    &mut ctx.accounts.token_inflow,
+    &mut ctx.accounts.voucher,
+    &ctx.accounts.mint,
+    &mut ctx.accounts.fanout_token_account,
+    Some(&ctx.accounts.destination.to_account_info()),
+    &ctx.accounts.token_program.to_account_info(),
+  )?;
+  emit_cpi!(event);
+
+  ctx
+    .accounts
+    .voucher
+    .set_shares(wallet_share.shares, &ctx.accounts.token_inflow);
+
+  Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use super::distributable_lamports;
+use crate::{
+  errors::ErrorCode,
+  events::TreasuryClaimedV0,
+  state::{FanoutV0, TokenInflowV0},
+};
+
+/// Pays the unissued portion of the native inflow to the fanout's treasury, or to the authority
+/// under Retain if a previous treasury was still owed
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimTreasurySolV0<'info> {
+  #[account(
+    mut,
+    constraint = fanout.treasury() == Some(treasury.key()) @ ErrorCode::InvalidTreasury,
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), Pubkey::default().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  /// CHECK: Checked against `FanoutV0::treasury`
+  #[account(mut)]
+  pub treasury: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<ClaimTreasurySolV0>) -> Result<()> {
+  require!(
+    !ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout),
+    ErrorCode::FanoutPaused
+  );
+
+  let balance = distributable_lamports(&ctx.accounts.fanout.to_account_info())?;
+  ctx
+    .accounts
+    .token_inflow
+    .update_total_inflow(balance, &ctx.accounts.fanout);
+  let amount = ctx.accounts.token_inflow.treasury_amount;
+  require_gt!(amount, 0, ErrorCode::NoTreasuryAmount);
+
+  // The fanout is owned by this program, so lamports can be moved directly
+  ctx.accounts.fanout.sub_lamports(amount)?;
+  ctx.accounts.treasury.add_lamports(amount)?;
+
+  ctx.accounts.token_inflow.treasury_amount = 0;
+  ctx.accounts.token_inflow.last_snapshot_amount =
+    distributable_lamports(&ctx.accounts.fanout.to_account_info())?;
+
+  emit_cpi!(TreasuryClaimedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    token_inflow: ctx.accounts.token_inflow.key(),
+    mint: Pubkey::default(),
+    treasury: ctx.accounts.treasury.key(),
+    amount,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::{
+  errors::ErrorCode,
+  events::TreasuryClaimedV0,
+  fanout_seeds,
+  state::{FanoutV0, TokenInflowV0},
+};
+
+/// Pays the unissued portion of a token inflow to the fanout's treasury, or to the authority under
+/// Retain if a previous treasury was still owed. The treasury receives the amount net of any
+/// transfer fee.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimTreasuryV0<'info> {
+  #[account(
+    constraint = fanout.treasury() == Some(treasury.key()) @ ErrorCode::InvalidTreasury,
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), mint.key().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fanout,
+        associated_token::token_program = token_program,
+    )]
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  /// CHECK: Checked against `FanoutV0::treasury`
+  pub treasury: AccountInfo<'info>,
+
+  #[account(
+        mut,
+        token::mint = mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+    )]
+  pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ClaimTreasuryV0>) -> Result<()> {
+  require!(
+    !ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout),
+    ErrorCode::FanoutPaused
+  );
+
+  ctx.accounts.token_inflow.update_total_inflow(
+    ctx.accounts.fanout_token_account.amount,
+    &ctx.accounts.fanout,
+  );
+  let amount = ctx.accounts.token_inflow.treasury_amount;
+  require_gt!(amount, 0, ErrorCode::NoTreasuryAmount);
+
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.fanout_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.treasury_token_account.to_account_info(),
+        authority: ctx.accounts.fanout.to_account_info(),
+      },
+      &[fanout_seeds!(ctx.accounts.fanout)],
+    ),
+    amount,
+    ctx.accounts.mint.decimals,
+  )?;
+  ctx.accounts.fanout_token_account.reload()?;
+
+  ctx.accounts.token_inflow.treasury_amount = 0;
+  ctx.accounts.token_inflow.last_snapshot_amount = ctx.accounts.fanout_token_account.amount;
+
+  emit_cpi!(TreasuryClaimedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    token_inflow: ctx.accounts.token_inflow.key(),
+    mint: ctx.accounts.mint.key(),
+    treasury: ctx.accounts.treasury.key(),
+    amount,
+  });
+
+  Ok(())
+}
@@ -1,34 +1,37 @@
-use anchor_lang::{prelude::*, solana_program::instruction::Instruction, InstructionData};
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack};
 use anchor_spl::{
-  associated_token::get_associated_token_address,
-  token::{self, spl_token, Mint, Token, TokenAccount},
-};
-use tuktuk_program::{
-  compile_transaction,
-  cron::{
-    accounts::{CronJobTransactionV0, CronJobV0},
-    cpi::{
-      accounts::{AddCronTransactionV0, RemoveCronTransactionV0},
-      add_cron_transaction_v0, remove_cron_transaction_v0,
+  associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken},
+  token_2022::spl_token_2022::{
+    self,
+    extension::{
+      memo_transfer::MemoTransfer, transfer_fee::TransferFeeConfig, BaseStateWithExtensions,
+      ExtensionType, StateWithExtensions,
     },
-    program::Cron,
-    types::{AddCronTransactionArgsV0, RemoveCronTransactionArgsV0},
+    state::AccountState,
   },
+  token_interface::{self, get_mint_extension_data, Mint, TokenAccount, TokenInterface},
+};
+use tuktuk_program::cron::{
+  accounts::{CronJobTransactionV0, CronJobV0},
+  program::Cron,
 };
 
 use crate::{
-  fanout_seeds, queue_authority_seeds,
-  state::{FanoutV0, TokenInflowV0, VoucherV0},
+  errors::ErrorCode,
+  events::{emit_optional_cpi, VoucherClaimWithheldV0, VoucherClaimedV0},
+  fanout_seeds,
+  program::WalletFanout,
+  state::{FanoutV0, TokenInflowV0, TransferFeePolicy, VoucherV0},
   WalletShareV0,
 };
 
 #[derive(Accounts)]
 pub struct ClaimV0<'info> {
-  #[account(
-        has_one = cron_job
-    )]
   pub fanout: Account<'info, FanoutV0>,
-  #[account(mut)]
+  #[account(
+    mut,
+    constraint = token_inflow.runs_on(&fanout, &cron_job.key()) @ ErrorCode::InvalidCronJob,
+  )]
   pub cron_job: Box<Account<'info, CronJobV0>>,
   #[account(
     mut,
@@ -60,176 +63,374 @@ pub struct ClaimV0<'info> {
         has_one = wallet_share,
     )]
   pub voucher: Account<'info, VoucherV0>,
-  pub wallet_share: Account<'info, WalletShareV0>,
+  /// CHECK: Loaded with WalletShareV0::load, which also reads wallet shares that aren't migrated
+  #[account(owner = crate::ID)]
+  pub wallet_share: AccountInfo<'info>,
   /// CHECK: Checked by has_one
   pub wallet: AccountInfo<'info>,
 
-  pub mint: Account<'info, Mint>,
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = fanout
+        associated_token::authority = fanout,
+        associated_token::token_program = token_program,
     )]
-  pub fanout_token_account: Account<'info, TokenAccount>,
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = wallet
+        address = get_associated_token_address_with_program_id(&wallet.key(), &mint.key(), &token_program.key()),
     )]
-  pub receiver_token_account: Account<'info, TokenAccount>,
+  /// CHECK: The wallet's associated token account, created by this instruction if missing
+  pub receiver_token_account: AccountInfo<'info>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub cron_program: Program<'info, Cron>,
   pub system_program: Program<'info, System>,
+  // Optional so that cron transactions compiled before these accounts existed keep working
+  #[account(
+    mut,
+    seeds = [b"ata_payer", fanout.key().as_ref()],
+    bump,
+  )]
+  pub ata_payer: Option<SystemAccount<'info>>,
+  pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
-const TWELVE_PREC: u128 = 1_000000000000;
+/// The PDA that pays rent for receiver token accounts created during a voucher's first payout.
+/// Anyone can fund it with a plain SOL transfer
+pub fn ata_payer_key(fanout: &Pubkey) -> Pubkey {
+  Pubkey::find_program_address(&[b"ata_payer", fanout.as_ref()], &crate::ID).0
+}
 
-pub fn handler(ctx: Context<ClaimV0>) -> Result<()> {
-  let token_inflow = &mut ctx.accounts.token_inflow;
-  let voucher = &mut ctx.accounts.voucher;
-  let fanout = &mut ctx.accounts.fanout;
-  token_inflow.update_total_inflow(ctx.accounts.fanout_token_account.amount, fanout);
-  // Calculate claimable amount
-  let total_inflow_change = token_inflow
-    .total_inflow
-    .checked_sub(voucher.last_claimed_inflow)
-    .unwrap();
+/// The size of the associated token account for `mint`, matching what the associated token program
+/// allocates
+pub fn associated_token_account_len(mint: &AccountInfo) -> Result<usize> {
+  if *mint.owner != spl_token_2022::ID {
+    return Ok(spl_token_2022::state::Account::LEN);
+  }
+  let data = mint.try_borrow_data()?;
+  let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+  let mut extension_types =
+    ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+  extension_types.push(ExtensionType::ImmutableOwner);
+  Ok(ExtensionType::try_calculate_account_len::<
+    spl_token_2022::state::Account,
+  >(&extension_types)?)
+}
 
-  let dist_amount = (total_inflow_change as u128)
-    .checked_mul(TWELVE_PREC)
-    .unwrap()
-    .checked_mul(voucher.shares as u128)
-    .unwrap()
-    .checked_div(fanout.total_shares as u128)
-    .unwrap();
+/// Creates the receiver's associated token account if it is missing, paying rent from the ATA payer.
+/// Returns whether the account exists afterwards.
+#[allow(clippy::too_many_arguments)]
+pub fn create_receiver_token_account<'info>(
+  fanout: &AccountInfo<'info>,
+  ata_payer: Option<&SystemAccount<'info>>,
+  ata_payer_bump: Option<u8>,
+  receiver_token_account: &AccountInfo<'info>,
+  wallet: &AccountInfo<'info>,
+  mint: &AccountInfo<'info>,
+  token_program: &AccountInfo<'info>,
+  system_program: &AccountInfo<'info>,
+  associated_token_program: Option<&AccountInfo<'info>>,
+) -> Result<bool> {
+  if !receiver_token_account.data_is_empty() {
+    return Ok(true);
+  }
+  let (Some(ata_payer), Some(ata_payer_bump), Some(associated_token_program)) =
+    (ata_payer, ata_payer_bump, associated_token_program)
+  else {
+    return Ok(false);
+  };
 
-  let mut dist_amount_u64: u64 = dist_amount
-    .checked_div(TWELVE_PREC)
-    .unwrap()
-    .try_into()
-    .unwrap();
+  // The payer is a plain system account, so it has to stay rent exempt or be fully drained
+  let rent = Rent::get()?;
+  let required = rent
+    .minimum_balance(associated_token_account_len(mint)?)
+    .saturating_sub(receiver_token_account.lamports());
+  let available = ata_payer.lamports();
+  if available != required && available < required.saturating_add(rent.minimum_balance(0)) {
+    msg!(
+      "ATA payer has {} lamports, {} needed to create the receiver token account",
+      available,
+      required
+    );
+    return Ok(false);
+  }
 
-  let dust: u64 = dist_amount
-    .checked_sub((dist_amount_u64 as u128).checked_mul(TWELVE_PREC).unwrap())
-    .unwrap()
-    .try_into()
-    .unwrap();
-  let new_dust = dust + voucher.total_dust;
-  let whole_dust = new_dust
-    .checked_div(u64::try_from(TWELVE_PREC).unwrap())
-    .unwrap();
-  if whole_dust > 1 {
-    dist_amount_u64 = dist_amount_u64.checked_add(1).unwrap();
-    voucher.total_dust = new_dust
-      .checked_sub(
-        whole_dust
-          .checked_mul(u64::try_from(TWELVE_PREC).unwrap())
-          .unwrap(),
-      )
-      .unwrap();
-  } else {
-    voucher.total_dust = new_dust;
+  associated_token::create_idempotent(CpiContext::new_with_signer(
+    associated_token_program.clone(),
+    associated_token::Create {
+      payer: ata_payer.to_account_info(),
+      associated_token: receiver_token_account.clone(),
+      authority: wallet.clone(),
+      mint: mint.clone(),
+      system_program: system_program.clone(),
+      token_program: token_program.clone(),
+    },
+    &[&[b"ata_payer", fanout.key().as_ref(), &[ata_payer_bump]]],
+  ))?;
+
+  Ok(true)
+}
+
+/// The transfer fee config of the mint, if it is a Token-2022 mint with the transfer fee extension
+pub fn transfer_fee_config(mint: &AccountInfo) -> Option<TransferFeeConfig> {
+  if *mint.owner != spl_token_2022::ID {
+    return None;
   }
+  get_mint_extension_data::<TransferFeeConfig>(mint).ok()
+}
 
-  msg!(
-    "Total inflow change: {}, dist amount: {}, dust: {}",
-    total_inflow_change,
-    dist_amount_u64,
-    dust
-  );
+/// Whether `receiver_token_account` can accept a transfer right now. Closed, frozen, foreign-owned and
+/// memo-required accounts would revert the claim, so their payouts are escrowed instead
+pub fn can_receive(
+  receiver_token_account: &AccountInfo,
+  token_program: &AccountInfo,
+) -> Result<bool> {
+  if receiver_token_account.data_is_empty() || receiver_token_account.owner != token_program.key {
+    return Ok(false);
+  }
+  let data = receiver_token_account.try_borrow_data()?;
+  let Ok(account) = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data) else {
+    return Ok(false);
+  };
+  if account.base.state != AccountState::Initialized {
+    return Ok(false);
+  }
+  let requires_memo = account
+    .get_extension::<MemoTransfer>()
+    .map(|memo_transfer| bool::from(memo_transfer.require_incoming_transfer_memos))
+    .unwrap_or_default();
 
+  Ok(!requires_memo)
+}
+
+/// Pays everything owed to `voucher` into `receiver_token_account`, keeping the inflow snapshot in
+/// step with the fanout's balance. Without a receiver the payout is added to the voucher's
+/// `pending_amount` and stays in the fanout until a later claim can deliver it
+#[allow(clippy::too_many_arguments)]
+pub fn claim_tokens<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  token_inflow: &mut Account<'info, TokenInflowV0>,
+  voucher: &mut Account<'info, VoucherV0>,
+  mint: &InterfaceAccount<'info, Mint>,
+  fanout_token_account: &mut InterfaceAccount<'info, TokenAccount>,
+  receiver_token_account: Option<&AccountInfo<'info>>,
+  token_program: &AccountInfo<'info>,
+) -> Result<VoucherClaimedV0> {
+  token_inflow.update_total_inflow(fanout_token_account.amount, fanout);
+  let dist_amount_u64 = voucher.calculate_claim(token_inflow);
   if dist_amount_u64 > 0 {
-    // Transfer tokens
-    token::transfer(
-      CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.fanout_token_account.to_account_info(),
-          to: ctx.accounts.receiver_token_account.to_account_info(),
-          authority: fanout.to_account_info(),
-        },
-        &[fanout_seeds!(fanout)],
-      ),
+    voucher.last_claimed_inflow = token_inflow.total_inflow;
+  }
+
+  let Some(receiver_token_account) = receiver_token_account else {
+    if dist_amount_u64 > 0 {
+      msg!(
+        "Receiver token account can't receive, escrowing {}",
+        dist_amount_u64
+      );
+      voucher.add_pending(dist_amount_u64, Clock::get()?.unix_timestamp);
+    }
+    return Ok(VoucherClaimedV0::new(
+      voucher,
+      token_inflow,
+      None,
       dist_amount_u64,
-    )?;
-    ctx.accounts.fanout_token_account.reload()?;
+      0,
+    ));
+  };
 
-    // Update voucher
-    voucher.last_claimed_inflow = token_inflow.total_inflow;
-    token_inflow.last_snapshot_amount = ctx.accounts.fanout_token_account.amount;
+  let pending_paid = voucher.pending_amount;
+  let amount = dist_amount_u64.checked_add(voucher.pending_amount).unwrap();
+  if amount > 0 {
+    transfer_from_fanout(
+      fanout,
+      token_inflow,
+      voucher,
+      mint,
+      fanout_token_account,
+      receiver_token_account,
+      token_program,
+      amount,
+    )?;
+    voucher.pending_amount = 0;
   }
 
-  voucher.shares = ctx.accounts.wallet_share.shares;
-  voucher.wallet = ctx.accounts.wallet_share.wallet;
-
-  // Update cron job if wallet differs
-  if voucher.wallet != ctx.accounts.wallet_share.wallet {
-    let ixs = vec![Instruction {
-      program_id: crate::ID,
-      accounts: crate::accounts::ClaimV0 {
-        fanout: fanout.key(),
-        token_inflow: ctx.accounts.token_inflow.key(),
-        voucher: ctx.accounts.voucher.key(),
-        mint: ctx.accounts.mint.key(),
-        fanout_token_account: ctx.accounts.fanout_token_account.key(),
-        receiver_token_account: get_associated_token_address(
-          &ctx.accounts.wallet_share.wallet,
-          &ctx.accounts.mint.key(),
-        ),
-        token_program: spl_token::ID,
-        wallet_share: ctx.accounts.wallet_share.key(),
-        wallet: ctx.accounts.wallet_share.wallet,
-        cron_job: ctx.accounts.cron_job.key(),
-        cron_job_transaction: ctx.accounts.cron_job_transaction.key(),
-        cron_program: ctx.accounts.cron_program.key(),
-        queue_authority: ctx.accounts.queue_authority.key(),
-        system_program: ctx.accounts.system_program.key(),
+  Ok(VoucherClaimedV0::new(
+    voucher,
+    token_inflow,
+    Some(receiver_token_account.key()),
+    dist_amount_u64,
+    pending_paid,
+  ))
+}
+
+/// Sends `amount` owed to `voucher` from the fanout to `destination`, applying the fanout's transfer
+/// fee policy. Expects `token_inflow` to already be updated to the current balance.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from_fanout<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  token_inflow: &mut Account<'info, TokenInflowV0>,
+  voucher: &mut Account<'info, VoucherV0>,
+  mint: &InterfaceAccount<'info, Mint>,
+  fanout_token_account: &mut InterfaceAccount<'info, TokenAccount>,
+  destination: &AccountInfo<'info>,
+  token_program: &AccountInfo<'info>,
+  amount: u64,
+) -> Result<()> {
+  let clock = Clock::get()?;
+  let epoch = clock.epoch;
+  let fee_config = transfer_fee_config(&mint.to_account_info());
+  let gross_amount = match (fee_config, fanout.transfer_fee_policy) {
+    (Some(config), TransferFeePolicy::Pool) => {
+      let pool_fee = config.calculate_inverse_epoch_fee(epoch, amount).unwrap();
+      // The rest of the balance is owed to other members and the treasury, so fall back to members
+      // absorbing the fee if what the pool retains can't cover it
+      if pool_fee <= token_inflow.retained_amount {
+        amount.checked_add(pool_fee).unwrap()
+      } else {
+        amount
       }
-      .to_account_metas(None),
-      data: crate::instruction::ClaimV0.data(),
-    }];
-    let (compiled_tx, _) = compile_transaction(ixs, vec![])?;
-
-    remove_cron_transaction_v0(
-      CpiContext::new_with_signer(
-        ctx.accounts.cron_program.to_account_info(),
-        RemoveCronTransactionV0 {
-          cron_job: ctx.accounts.cron_job.to_account_info(),
-          cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
-          authority: ctx.accounts.queue_authority.to_account_info(),
-          system_program: ctx.accounts.system_program.to_account_info(),
-          rent_refund: ctx.accounts.queue_authority.to_account_info(),
-        },
-        &[queue_authority_seeds!(ctx.accounts.fanout)],
-      ),
-      RemoveCronTransactionArgsV0 {
-        index: ctx.accounts.voucher.cron_transaction_id,
-      },
-    )?;
+    }
+    _ => amount,
+  };
+  let fee = fee_config
+    .map(|config| config.calculate_epoch_fee(epoch, gross_amount).unwrap())
+    .unwrap_or_default();
 
-    add_cron_transaction_v0(
-      CpiContext::new_with_signer(
-        ctx.accounts.cron_program.to_account_info(),
-        AddCronTransactionV0 {
-          cron_job: ctx.accounts.cron_job.to_account_info(),
-          cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
-          payer: ctx.accounts.queue_authority.to_account_info(),
-          authority: ctx.accounts.queue_authority.to_account_info(),
-          system_program: ctx.accounts.system_program.to_account_info(),
-        },
-        &[queue_authority_seeds!(ctx.accounts.fanout)],
-      ),
-      AddCronTransactionArgsV0 {
-        index: ctx.accounts.voucher.cron_transaction_id,
-        transaction_source: tuktuk_program::cron::types::TransactionSourceV0::CompiledV0(
-          compiled_tx.into(),
-        ),
+  msg!("Gross amount: {}, transfer fee: {}", gross_amount, fee);
+
+  // Transfer tokens
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      token_program.clone(),
+      token_interface::TransferChecked {
+        from: fanout_token_account.to_account_info(),
+        mint: mint.to_account_info(),
+        to: destination.clone(),
+        authority: fanout.to_account_info(),
       },
+      &[fanout_seeds!(fanout)],
+    ),
+    gross_amount,
+    mint.decimals,
+  )?;
+  fanout_token_account.reload()?;
+
+  // Update voucher
+  voucher.total_gross_claimed = voucher
+    .total_gross_claimed
+    .checked_add(gross_amount)
+    .unwrap();
+  voucher.total_net_claimed = voucher
+    .total_net_claimed
+    .checked_add(gross_amount.checked_sub(fee).unwrap())
+    .unwrap();
+  voucher.record_claim(amount, &clock);
+  // Anything paid beyond the member's share is owed back to the pool
+  let pool_fee = gross_amount.checked_sub(amount).unwrap();
+  token_inflow.retained_amount = token_inflow.retained_amount.checked_sub(pool_fee).unwrap();
+  token_inflow.unrecovered_fees = token_inflow.unrecovered_fees.checked_add(pool_fee).unwrap();
+  token_inflow.last_snapshot_amount = fanout_token_account.amount;
+
+  Ok(())
+}
+
+pub fn handler(ctx: Context<ClaimV0>) -> Result<()> {
+  let wallet_share = WalletShareV0::load(&ctx.accounts.wallet_share)?;
+
+  // The cron job keeps firing while paused, so skip the payout and let inflow keep accruing
+  if ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout) {
+    msg!("Payouts are paused, skipping claim");
+    return Ok(());
+  }
+
+  ctx.accounts.token_inflow.update_total_inflow(
+    ctx.accounts.fanout_token_account.amount,
+    &ctx.accounts.fanout,
+  );
+
+  // Suspended members keep accruing, held back on the voucher until unsuspended
+  if wallet_share.suspended {
+    let voucher = &mut ctx.accounts.voucher;
+    let amount = voucher.withhold_claim(&ctx.accounts.token_inflow);
+    voucher.set_shares(wallet_share.shares, &ctx.accounts.token_inflow);
+    emit_optional_cpi(
+      ctx.accounts.event_authority.as_ref(),
+      ctx.bumps.event_authority,
+      VoucherClaimWithheldV0::new(voucher, amount),
     )?;
+    return Ok(());
+  }
+
+  if ctx
+    .accounts
+    .voucher
+    .below_min_payout(&ctx.accounts.token_inflow)
+  {
+    msg!("Owed amount is below the minimum payout, skipping claim");
+    return Ok(());
   }
 
+  let associated_token_program = ctx
+    .accounts
+    .associated_token_program
+    .as_ref()
+    .map(|program| program.to_account_info());
+  // The ATA payer only funds a receiver for something owed, and only until the voucher's first
+  // payout. Otherwise a member could drain it by closing their token account between claims
+  let owed = ctx.accounts.voucher.owed(&ctx.accounts.token_inflow);
+  let exists = if owed > 0 && ctx.accounts.voucher.claim_count == 0 {
+    create_receiver_token_account(
+      &ctx.accounts.fanout.to_account_info(),
+      ctx.accounts.ata_payer.as_ref(),
+      ctx.bumps.ata_payer,
+      &ctx.accounts.receiver_token_account,
+      &ctx.accounts.wallet,
+      &ctx.accounts.mint.to_account_info(),
+      &ctx.accounts.token_program.to_account_info(),
+      &ctx.accounts.system_program.to_account_info(),
+      associated_token_program.as_ref(),
+    )?
+  } else {
+    !ctx.accounts.receiver_token_account.data_is_empty()
+  };
+  let has_receiver = exists
+    && can_receive(
+      &ctx.accounts.receiver_token_account,
+      &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+  let event = claim_tokens(
+    &ctx.accounts.fanout,
+    &mut ctx.accounts.token_inflow,
+    &mut ctx.accounts.voucher,
+    &ctx.accounts.mint,
+    &mut ctx.accounts.fanout_token_account,
+    has_receiver.then_some(&ctx.accounts.receiver_token_account),
+    &ctx.accounts.token_program.to_account_info(),
+  )?;
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    event,
+  )?;
+
+  // Wallet changes are applied by rotate_voucher_wallet_v0, which also rewrites the cron transaction
+  ctx
+    .accounts
+    .voucher
+    .set_shares(wallet_share.shares, &ctx.accounts.token_inflow);
+
   Ok(())
 }
@@ -5,7 +5,12 @@ use tuktuk_program::cron::{
   program::Cron,
 };
 
-use crate::{queue_authority_seeds, state::FanoutV0};
+use crate::{
+  events::{emit_optional_cpi, FanoutClosedV0},
+  program::WalletFanout,
+  queue_authority_seeds,
+  state::FanoutV0,
+};
 
 #[derive(Accounts)]
 pub struct CloseFanoutV0<'info> {
@@ -44,6 +49,14 @@ pub struct CloseFanoutV0<'info> {
 
   pub cron_program: Program<'info, Cron>,
   pub system_program: Program<'info, System>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
 pub fn handler(ctx: Context<CloseFanoutV0>) -> Result<()> {
@@ -63,5 +76,13 @@ pub fn handler(ctx: Context<CloseFanoutV0>) -> Result<()> {
     &[queue_authority_seeds!(ctx.accounts.fanout)],
   ))?;
 
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    FanoutClosedV0 {
+      fanout: ctx.accounts.fanout.key(),
+    },
+  )?;
+
   Ok(())
 }
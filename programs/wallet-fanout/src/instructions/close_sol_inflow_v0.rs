@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use tuktuk_program::cron::{accounts::CronJobV0, cpi::accounts::CloseCronJobV0, program::Cron};
+
+use super::{close_inflow_cron_job, distributable_lamports, required_cron_account};
+use crate::{
+  errors::ErrorCode,
+  events::TokenInflowClosedV0,
+  state::{FanoutV0, TokenInflowV0},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseSolInflowV0<'info> {
+  #[account(
+    mut,
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+        mut,
+        close = rent_refund,
+        has_one = rent_refund,
+        has_one = fanout,
+        seeds = [b"token_inflow", fanout.key().as_ref(), Pubkey::default().as_ref()],
+        bump = token_inflow.bump,
+        constraint = token_inflow.num_vouchers == 0,
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  /// CHECK: This is the account that will receive the rent
+  #[account(mut)]
+  pub rent_refund: AccountInfo<'info>,
+
+  // Only needed when the inflow has its own cron job, which is closed with it
+  #[account(
+    mut,
+    constraint = token_inflow.cron_job == Some(cron_job.key()) @ ErrorCode::InvalidCronJob,
+  )]
+  pub cron_job: Option<Account<'info, CronJobV0>>,
+  #[account(
+        seeds = [b"queue_authority"],
+        bump = fanout.queue_authority_bump,
+    )]
+  /// CHECK: Used for signing
+  pub queue_authority: Option<AccountInfo<'info>>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub user_cron_jobs: Option<AccountInfo<'info>>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub cron_job_name_mapping: Option<AccountInfo<'info>>,
+  /// CHECK: Used in CPI
+  #[account(mut)]
+  pub task_return_account_1: Option<AccountInfo<'info>>,
+  /// CHECK: Used in CPI
+  #[account(mut)]
+  pub task_return_account_2: Option<AccountInfo<'info>>,
+  pub cron_program: Option<Program<'info, Cron>>,
+  pub system_program: Option<Program<'info, System>>,
+}
+
+pub fn handler(ctx: Context<CloseSolInflowV0>) -> Result<()> {
+  // Whatever the treasury is owed, including from inflow that arrived since the last update, is not
+  // the authority's to sweep
+  let remaining = distributable_lamports(&ctx.accounts.fanout.to_account_info())?;
+  ctx
+    .accounts
+    .token_inflow
+    .update_total_inflow(remaining, &ctx.accounts.fanout);
+  require_eq!(
+    ctx.accounts.token_inflow.treasury_amount,
+    0,
+    ErrorCode::TreasuryAmountOwed
+  );
+
+  // Decrement fanout's inflow count
+  ctx.accounts.fanout.num_inflows = ctx.accounts.fanout.num_inflows.checked_sub(1).unwrap();
+
+  // Transfer remaining lamports above rent to authority
+  if remaining > 0 {
+    ctx.accounts.fanout.sub_lamports(remaining)?;
+    ctx.accounts.authority.add_lamports(remaining)?;
+  }
+
+  if ctx.accounts.token_inflow.cron_job.is_some() {
+    close_inflow_cron_job(
+      &ctx.accounts.fanout,
+      required_cron_account(&ctx.accounts.cron_program)?,
+      CloseCronJobV0 {
+        cron_job: required_cron_account(&ctx.accounts.cron_job)?,
+        authority: required_cron_account(&ctx.accounts.queue_authority)?,
+        rent_refund: ctx.accounts.authority.to_account_info(),
+        system_program: required_cron_account(&ctx.accounts.system_program)?,
+        user_cron_jobs: required_cron_account(&ctx.accounts.user_cron_jobs)?,
+        cron_job_name_mapping: required_cron_account(&ctx.accounts.cron_job_name_mapping)?,
+        task_return_account_1: required_cron_account(&ctx.accounts.task_return_account_1)?,
+        task_return_account_2: required_cron_account(&ctx.accounts.task_return_account_2)?,
+      },
+    )?;
+  }
+
+  emit_cpi!(TokenInflowClosedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    token_inflow: ctx.accounts.token_inflow.key(),
+    mint: Pubkey::default(),
+  });
+
+  Ok(())
+}
@@ -1,14 +1,49 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::program::invoke};
 use anchor_spl::{
   associated_token::AssociatedToken,
-  token::{self, Mint, Token, TokenAccount},
+  token_2022::spl_token_2022::extension::transfer_fee::instruction::harvest_withheld_tokens_to_mint,
+  token_interface::{self, Mint, TokenAccount, TokenInterface},
+};
+use tuktuk_program::cron::{
+  accounts::CronJobV0,
+  cpi::{accounts::CloseCronJobV0, close_cron_job_v0},
+  program::Cron,
 };
 
+use super::transfer_fee_config;
 use crate::{
+  errors::ErrorCode,
+  events::{emit_optional_cpi, TokenInflowClosedV0},
   fanout_seeds,
+  program::WalletFanout,
+  queue_authority_seeds,
   state::{FanoutV0, TokenInflowV0},
 };
 
+/// Unwraps an optional account that is only passed when the inflow has its own cron job
+pub fn required_cron_account<'info, T: ToAccountInfo<'info>>(
+  account: &Option<T>,
+) -> Result<AccountInfo<'info>> {
+  account
+    .as_ref()
+    .map(|a| a.to_account_info())
+    .ok_or_else(|| error!(ErrorCode::InvalidCronJob))
+}
+
+/// Closes the inflow's own cron job. Every voucher of the inflow is closed by now, so it has no
+/// transactions left
+pub fn close_inflow_cron_job<'info>(
+  fanout: &FanoutV0,
+  cron_program: AccountInfo<'info>,
+  accounts: CloseCronJobV0<'info>,
+) -> Result<()> {
+  close_cron_job_v0(CpiContext::new_with_signer(
+    cron_program,
+    accounts,
+    &[queue_authority_seeds!(fanout)],
+  ))
+}
+
 #[derive(Accounts)]
 pub struct CloseTokenInflowV0<'info> {
   #[account(
@@ -28,56 +63,124 @@ pub struct CloseTokenInflowV0<'info> {
         constraint = token_inflow.num_vouchers == 0,
     )]
   pub token_inflow: Account<'info, TokenInflowV0>,
-  pub mint: Account<'info, Mint>,
+  // Fees withheld in the fanout's token account are harvested to the mint before it is closed
+  #[account(mut)]
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = mint,
     associated_token::authority = fanout,
+    associated_token::token_program = token_program,
   )]
-  pub fanout_token_account: Account<'info, TokenAccount>,
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
         init_if_needed,
         payer = authority,
         associated_token::mint = mint,
         associated_token::authority = authority,
+        associated_token::token_program = token_program,
     )]
-  pub authority_token_account: Account<'info, TokenAccount>,
+  pub authority_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
   /// CHECK: This is the account that will receive the rent
   #[account(mut)]
   pub rent_refund: AccountInfo<'info>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
+
+  // Only needed when the inflow has its own cron job, which is closed with it
+  #[account(
+    mut,
+    constraint = token_inflow.cron_job == Some(cron_job.key()) @ ErrorCode::InvalidCronJob,
+  )]
+  pub cron_job: Option<Account<'info, CronJobV0>>,
+  #[account(
+        seeds = [b"queue_authority"],
+        bump = fanout.queue_authority_bump,
+    )]
+  /// CHECK: Used for signing
+  pub queue_authority: Option<AccountInfo<'info>>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub user_cron_jobs: Option<AccountInfo<'info>>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub cron_job_name_mapping: Option<AccountInfo<'info>>,
+  /// CHECK: Used in CPI
+  #[account(mut)]
+  pub task_return_account_1: Option<AccountInfo<'info>>,
+  /// CHECK: Used in CPI
+  #[account(mut)]
+  pub task_return_account_2: Option<AccountInfo<'info>>,
+  pub cron_program: Option<Program<'info, Cron>>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
 pub fn handler(ctx: Context<CloseTokenInflowV0>) -> Result<()> {
+  // Whatever the treasury is owed, including from inflow that arrived since the last update, is not
+  // the authority's to sweep
+  ctx.accounts.token_inflow.update_total_inflow(
+    ctx.accounts.fanout_token_account.amount,
+    &ctx.accounts.fanout,
+  );
+  require_eq!(
+    ctx.accounts.token_inflow.treasury_amount,
+    0,
+    ErrorCode::TreasuryAmountOwed
+  );
+
   // Decrement fanout's inflow count
   ctx.accounts.fanout.num_inflows = ctx.accounts.fanout.num_inflows.checked_sub(1).unwrap();
 
   // Transfer remaining tokens to authority
   if ctx.accounts.fanout_token_account.amount > 0 {
-    token::transfer(
+    token_interface::transfer_checked(
       CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
+        token_interface::TransferChecked {
           from: ctx.accounts.fanout_token_account.to_account_info(),
+          mint: ctx.accounts.mint.to_account_info(),
           to: ctx.accounts.authority_token_account.to_account_info(),
           authority: ctx.accounts.fanout.to_account_info(),
         },
         &[fanout_seeds!(ctx.accounts.fanout)],
       ),
       ctx.accounts.fanout_token_account.amount,
+      ctx.accounts.mint.decimals,
+    )?;
+  }
+
+  // Token-2022 won't close an account that still holds withheld transfer fees
+  if transfer_fee_config(&ctx.accounts.mint.to_account_info()).is_some() {
+    invoke(
+      &harvest_withheld_tokens_to_mint(
+        ctx.accounts.token_program.key,
+        &ctx.accounts.mint.key(),
+        &[&ctx.accounts.fanout_token_account.key()],
+      )?,
+      &[
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.fanout_token_account.to_account_info(),
+      ],
     )?;
   }
 
   // Close the token account
-  token::close_account(CpiContext::new_with_signer(
+  token_interface::close_account(CpiContext::new_with_signer(
     ctx.accounts.token_program.to_account_info(),
-    token::CloseAccount {
+    token_interface::CloseAccount {
       account: ctx.accounts.fanout_token_account.to_account_info(),
       destination: ctx.accounts.rent_refund.to_account_info(),
       authority: ctx.accounts.fanout.to_account_info(),
@@ -85,5 +188,32 @@ pub fn handler(ctx: Context<CloseTokenInflowV0>) -> Result<()> {
     &[fanout_seeds!(ctx.accounts.fanout)],
   ))?;
 
+  if ctx.accounts.token_inflow.cron_job.is_some() {
+    close_inflow_cron_job(
+      &ctx.accounts.fanout,
+      required_cron_account(&ctx.accounts.cron_program)?,
+      CloseCronJobV0 {
+        cron_job: required_cron_account(&ctx.accounts.cron_job)?,
+        authority: required_cron_account(&ctx.accounts.queue_authority)?,
+        rent_refund: ctx.accounts.authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        user_cron_jobs: required_cron_account(&ctx.accounts.user_cron_jobs)?,
+        cron_job_name_mapping: required_cron_account(&ctx.accounts.cron_job_name_mapping)?,
+        task_return_account_1: required_cron_account(&ctx.accounts.task_return_account_1)?,
+        task_return_account_2: required_cron_account(&ctx.accounts.task_return_account_2)?,
+      },
+    )?;
+  }
+
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    TokenInflowClosedV0 {
+      fanout: ctx.accounts.fanout.key(),
+      token_inflow: ctx.accounts.token_inflow.key(),
+      mint: ctx.accounts.mint.key(),
+    },
+  )?;
+
   Ok(())
 }
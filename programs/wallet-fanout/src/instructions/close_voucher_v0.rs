@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
 use tuktuk_program::cron::{
   accounts::CronJobV0,
   cpi::{accounts::RemoveCronTransactionV0, remove_cron_transaction_v0},
@@ -8,6 +8,9 @@ use tuktuk_program::cron::{
 };
 
 use crate::{
+  errors::ErrorCode,
+  events::{emit_optional_cpi, VoucherClosedV0},
+  program::WalletFanout,
   queue_authority_seeds,
   resize_to_fit::resize_to_fit,
   state::{FanoutV0, TokenInflowV0, VoucherV0},
@@ -18,7 +21,6 @@ pub struct CloseVoucherV0<'info> {
   #[account(
         mut,
         has_one = authority,
-        has_one = cron_job,
     )]
   pub fanout: Account<'info, FanoutV0>,
   pub authority: Signer<'info>,
@@ -28,8 +30,9 @@ pub struct CloseVoucherV0<'info> {
   #[account(
     associated_token::mint = token_inflow.mint,
     associated_token::authority = fanout,
+    associated_token::token_program = token_program,
   )]
-  pub fanout_token_account: Account<'info, TokenAccount>,
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
   #[account(
         mut,
         close = rent_refund,
@@ -49,7 +52,10 @@ pub struct CloseVoucherV0<'info> {
   #[account(mut)]
   pub rent_refund: AccountInfo<'info>,
 
-  #[account(mut)]
+  #[account(
+    mut,
+    constraint = token_inflow.runs_on(&fanout, &cron_job.key()) @ ErrorCode::InvalidCronJob,
+  )]
   pub cron_job: Box<Account<'info, CronJobV0>>,
 
   #[account(
@@ -72,6 +78,15 @@ pub struct CloseVoucherV0<'info> {
   pub payer: Signer<'info>,
   pub system_program: Program<'info, System>,
   pub cron_program: Program<'info, Cron>,
+  pub token_program: Interface<'info, TokenInterface>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
 pub fn handler(ctx: Context<CloseVoucherV0>) -> Result<()> {
@@ -80,10 +95,19 @@ pub fn handler(ctx: Context<CloseVoucherV0>) -> Result<()> {
     &ctx.accounts.fanout,
   );
 
+  ctx
+    .accounts
+    .voucher
+    .initialize_accumulator(&ctx.accounts.token_inflow);
+  require_eq!(
+    ctx.accounts.voucher.unclaimed(&ctx.accounts.token_inflow),
+    0,
+    ErrorCode::RewardsNotClaimed
+  );
   require_eq!(
-    ctx.accounts.token_inflow.total_inflow,
-    ctx.accounts.voucher.last_claimed_inflow,
-    crate::errors::ErrorCode::RewardsNotClaimed
+    ctx.accounts.voucher.pending_amount,
+    0,
+    ErrorCode::PendingAmountNotPaid
   );
 
   // Decrement voucher count
@@ -124,5 +148,16 @@ pub fn handler(ctx: Context<CloseVoucherV0>) -> Result<()> {
     &ctx.accounts.fanout,
   )?;
 
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    VoucherClosedV0 {
+      fanout: ctx.accounts.fanout.key(),
+      voucher: ctx.accounts.voucher.key(),
+      wallet_share: ctx.accounts.voucher.wallet_share,
+      mint: ctx.accounts.voucher.mint,
+    },
+  )?;
+
   Ok(())
 }
@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{FanoutV0, WalletShareV0};
+use crate::{
+  errors::ErrorCode,
+  events::{emit_optional_cpi, WalletShareUpdatedV0},
+  program::WalletFanout,
+  settle::settle_wallet_share,
+  state::{FanoutV0, WalletShareV0},
+};
 
+/// Remaining accounts are the same as for update_wallet_share_v0
 #[derive(Accounts)]
 pub struct CloseWalletShareV0<'info> {
   #[account(
@@ -15,19 +22,49 @@ pub struct CloseWalletShareV0<'info> {
         has_one = fanout,
         close = rent_refund,
         has_one = rent_refund,
+        constraint = !wallet_share.suspended @ ErrorCode::WalletShareSuspended,
     )]
   pub wallet_share: Account<'info, WalletShareV0>,
 
   /// CHECK: This is the account that will receive the rent
   #[account(mut)]
   pub rent_refund: AccountInfo<'info>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
-pub fn handler(ctx: Context<CloseWalletShareV0>) -> Result<()> {
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, CloseWalletShareV0<'info>>) -> Result<()> {
+  // Vouchers stop accruing once the share is gone
+  settle_wallet_share(
+    &ctx.accounts.fanout,
+    &ctx.accounts.wallet_share,
+    0,
+    ctx.remaining_accounts,
+  )?;
+
   let fanout = &mut ctx.accounts.fanout;
   fanout.total_shares_issued = fanout
     .total_shares_issued
     .checked_sub(ctx.accounts.wallet_share.shares)
     .unwrap();
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    WalletShareUpdatedV0 {
+      fanout: fanout.key(),
+      wallet_share: ctx.accounts.wallet_share.key(),
+      wallet: ctx.accounts.wallet_share.wallet,
+      old_shares: ctx.accounts.wallet_share.shares,
+      new_shares: 0,
+      total_shares_issued: fanout.total_shares_issued,
+    },
+  )?;
+
   Ok(())
 }
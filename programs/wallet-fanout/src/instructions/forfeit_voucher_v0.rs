@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  errors::ErrorCode,
+  events::VoucherForfeitedV0,
+  settle::inflow_balance,
+  state::{
+    FanoutV0, ForfeitDestination, TokenInflowV0, UnissuedSharePolicy, VoucherV0, WalletShareV0,
+  },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ForfeitVoucherV0Args {
+  pub destination: ForfeitDestination,
+}
+
+/// Takes back everything a voucher is owed once its pending amount has gone undelivered for the
+/// fanout's claim window, so vouchers of dead wallets can be closed
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ForfeitVoucherV0<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+    has_one = authority,
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), voucher.mint.as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+  #[account(
+        mut,
+        has_one = fanout,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+  /// CHECK: Checked against the voucher, may already be closed
+  #[account(address = voucher.wallet_share)]
+  pub wallet_share: AccountInfo<'info>,
+  /// CHECK: The fanout's token account for the voucher's mint, or the fanout itself for SOL
+  pub balance_account: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<ForfeitVoucherV0>, args: ForfeitVoucherV0Args) -> Result<()> {
+  let fanout = &ctx.accounts.fanout;
+  // Nothing can be delivered while paused, and suspended members are handled by unsuspending them
+  require!(
+    !ctx.accounts.token_inflow.is_paused(fanout),
+    ErrorCode::FanoutPaused
+  );
+  require!(
+    !WalletShareV0::is_suspended(&ctx.accounts.wallet_share)?,
+    ErrorCode::WalletShareSuspended
+  );
+  let expires_unix_ts = ctx
+    .accounts
+    .voucher
+    .pending_expires_unix_ts(fanout)
+    .ok_or(ErrorCode::NothingToForfeit)?;
+  require_gte!(
+    Clock::get()?.unix_timestamp,
+    expires_unix_ts,
+    ErrorCode::ClaimWindowNotElapsed
+  );
+  if args.destination == ForfeitDestination::Treasury {
+    require!(
+      matches!(
+        fanout.unissued_share_policy,
+        UnissuedSharePolicy::Treasury(_)
+      ),
+      ErrorCode::InvalidTreasury
+    );
+  }
+
+  let balance = inflow_balance(
+    &fanout.to_account_info(),
+    &ctx.accounts.token_inflow,
+    &ctx.accounts.balance_account,
+  )?;
+  let token_inflow = &mut ctx.accounts.token_inflow;
+  token_inflow.update_total_inflow(balance, fanout);
+
+  // Whatever accrued since the last claim went undelivered as well
+  let voucher = &mut ctx.accounts.voucher;
+  let owed = voucher.calculate_claim(token_inflow);
+  voucher.last_claimed_inflow = token_inflow.total_inflow;
+  let amount = owed
+    .checked_add(std::mem::take(&mut voucher.pending_amount))
+    .unwrap();
+
+  match args.destination {
+    ForfeitDestination::Redistribute => token_inflow.redistribute(amount, fanout),
+    ForfeitDestination::Treasury => {
+      token_inflow.treasury_amount = token_inflow.treasury_amount.checked_add(amount).unwrap();
+    }
+  }
+
+  emit_cpi!(VoucherForfeitedV0 {
+    fanout: fanout.key(),
+    voucher: voucher.key(),
+    wallet_share: voucher.wallet_share,
+    mint: voucher.mint,
+    amount,
+    destination: args.destination,
+  });
+
+  Ok(())
+}
@@ -9,7 +9,11 @@ use tuktuk_program::{
   TaskQueueAuthorityV0, TaskQueueV0,
 };
 
-use crate::state::{FanoutV0, GlobalStateV0};
+use crate::{
+  events::{emit_optional_cpi, FanoutInitializedV0},
+  program::WalletFanout,
+  state::{FanoutV0, GlobalStateV0, TransferFeePolicy, UnissuedSharePolicy},
+};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct InitializeFanoutV0Args {
@@ -84,6 +88,14 @@ pub struct InitializeFanoutV0<'info> {
   pub system_program: Program<'info, System>,
   pub tuktuk_program: Program<'info, Tuktuk>,
   pub cron_program: Program<'info, Cron>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
 pub fn handler(ctx: Context<InitializeFanoutV0>, args: InitializeFanoutV0Args) -> Result<()> {
@@ -101,6 +113,12 @@ pub fn handler(ctx: Context<InitializeFanoutV0>, args: InitializeFanoutV0Args) -
     available_cron_transaction_ids: vec![],
     total_shares_issued: 0,
     num_inflows: 0,
+    transfer_fee_policy: TransferFeePolicy::Members,
+    pending_authority: None,
+    pending_schedule: None,
+    unissued_share_policy: UnissuedSharePolicy::Rebase,
+    paused: false,
+    claim_window_secs: None,
   });
 
   initialize_cron_job_v0(
@@ -131,5 +149,17 @@ pub fn handler(ctx: Context<InitializeFanoutV0>, args: InitializeFanoutV0Args) -
     },
   )?;
 
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    FanoutInitializedV0 {
+      fanout: ctx.accounts.fanout.key(),
+      authority: ctx.accounts.fanout.authority,
+      name: ctx.accounts.fanout.name.clone(),
+      total_shares: ctx.accounts.fanout.total_shares,
+      cron_job: ctx.accounts.fanout.cron_job,
+    },
+  )?;
+
   Ok(())
 }
@@ -43,6 +43,7 @@ pub fn handler(ctx: Context<InitializeGlobalStateV0>) -> Result<()> {
     authority: ctx.accounts.authority.key(),
     task_queue: ctx.accounts.task_queue.key(),
     bump: ctx.bumps.global_state,
+    pending_authority: None,
   });
 
   Ok(())
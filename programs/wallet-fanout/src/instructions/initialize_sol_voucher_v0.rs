@@ -0,0 +1,191 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, InstructionData};
+use tuktuk_program::{
+  compile_transaction,
+  cron::{
+    accounts::CronJobV0,
+    cpi::{accounts::AddCronTransactionV0, add_cron_transaction_v0},
+    program::Cron,
+    types::AddCronTransactionArgsV0,
+  },
+};
+
+use crate::{
+  errors::ErrorCode,
+  events::VoucherInitializedV0,
+  queue_authority_seeds,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeSolVoucherV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  #[account(mut)]
+  pub fanout: Account<'info, FanoutV0>,
+
+  #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + 60 + std::mem::size_of::<TokenInflowV0>(),
+        seeds = [b"token_inflow", fanout.key().as_ref(), Pubkey::default().as_ref()],
+        bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        init,
+        payer = payer,
+        space = 8 + 60 + std::mem::size_of::<VoucherV0>(),
+        seeds = [
+            b"voucher",
+            fanout.key().as_ref(),
+            Pubkey::default().as_ref(),
+            wallet_share.key().as_ref()
+        ],
+        bump
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+
+  /// CHECK: Loaded with WalletShareV0::load, which also reads wallet shares that aren't migrated
+  #[account(owner = crate::ID)]
+  pub wallet_share: AccountInfo<'info>,
+
+  #[account(
+    seeds = [b"queue_authority"],
+    bump = fanout.queue_authority_bump,
+  )]
+  /// CHECK: Used for signing
+  pub authority: AccountInfo<'info>,
+
+  #[account(
+    mut,
+    has_one = authority,
+    constraint = cron_job.key() == token_inflow.voucher_cron_job(&fanout) @ ErrorCode::InvalidCronJob,
+  )]
+  pub cron_job: Box<Account<'info, CronJobV0>>,
+  #[account(mut)]
+  /// CHECK: Init in CPI
+  pub cron_job_transaction: AccountInfo<'info>,
+  pub cron_program: Program<'info, Cron>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeSolVoucherV0>) -> Result<()> {
+  let wallet_share = WalletShareV0::load(&ctx.accounts.wallet_share)?;
+  require_keys_eq!(
+    wallet_share.fanout,
+    ctx.accounts.fanout.key(),
+    anchor_lang::error::ErrorCode::ConstraintHasOne
+  );
+
+  let fanout = &mut ctx.accounts.fanout;
+
+  // Initialize the native token inflow if needed
+  if ctx.accounts.token_inflow.fanout == Pubkey::default() {
+    ctx.accounts.token_inflow.set_inner(TokenInflowV0 {
+      fanout: fanout.key(),
+      mint: Pubkey::default(),
+      total_inflow: 0,
+      last_snapshot_amount: 0,
+      bump: ctx.bumps.token_inflow,
+      rent_refund: ctx.accounts.payer.key(),
+      num_vouchers: 0,
+      unrecovered_fees: 0,
+      total_shares: fanout.total_shares,
+      acc_per_share: 0,
+      accumulator_initialized: true,
+      treasury_amount: 0,
+      paused: false,
+      min_payout: 0,
+      cron_job: None,
+      retained_amount: 0,
+    });
+    // Increment fanout's inflow count
+    fanout.num_inflows = fanout.num_inflows.checked_add(1).unwrap();
+  }
+  ctx.accounts.token_inflow.initialize_accumulator(fanout);
+
+  ctx.accounts.token_inflow.num_vouchers = ctx
+    .accounts
+    .token_inflow
+    .num_vouchers
+    .checked_add(1)
+    .unwrap();
+
+  let cron_transaction_id = fanout.get_next_cron_transaction_id();
+
+  ctx.accounts.voucher.set_inner(VoucherV0 {
+    wallet: wallet_share.wallet,
+    fanout: fanout.key(),
+    mint: Pubkey::default(),
+    cron_transaction_id,
+    last_claimed_inflow: ctx.accounts.token_inflow.total_inflow,
+    total_dust: 0,
+    wallet_share: ctx.accounts.wallet_share.key(),
+    shares: wallet_share.shares,
+    rent_refund: ctx.accounts.payer.key(),
+    total_gross_claimed: 0,
+    total_net_claimed: 0,
+    pending_amount: 0,
+    reward_debt: (wallet_share.shares as u128)
+      .checked_mul(ctx.accounts.token_inflow.acc_per_share)
+      .unwrap(),
+    accumulator_initialized: true,
+    total_claimed: 0,
+    claim_count: 0,
+    last_claim_unix_ts: 0,
+    last_claim_slot: 0,
+    escrowed_amount: 0,
+    pending_since_unix_ts: 0,
+    min_payout: 0,
+  });
+
+  let ixs = vec![Instruction {
+    program_id: crate::ID,
+    accounts: crate::accounts::ClaimSolV0 {
+      fanout: fanout.key(),
+      token_inflow: ctx.accounts.token_inflow.key(),
+      voucher: ctx.accounts.voucher.key(),
+      wallet_share: ctx.accounts.wallet_share.key(),
+      wallet: wallet_share.wallet,
+      event_authority: Some(ctx.accounts.event_authority.key()),
+      program: Some(crate::ID),
+    }
+    .to_account_metas(None),
+    data: crate::instruction::ClaimSolV0.data(),
+  }];
+  let (compiled_tx, _) = compile_transaction(ixs, vec![])?;
+
+  add_cron_transaction_v0(
+    CpiContext::new_with_signer(
+      ctx.accounts.cron_program.to_account_info(),
+      AddCronTransactionV0 {
+        cron_job: ctx.accounts.cron_job.to_account_info(),
+        cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
+        payer: ctx.accounts.payer.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+      },
+      &[queue_authority_seeds!(ctx.accounts.fanout)],
+    ),
+    AddCronTransactionArgsV0 {
+      index: cron_transaction_id,
+      transaction_source: tuktuk_program::cron::types::TransactionSourceV0::CompiledV0(
+        compiled_tx.into(),
+      ),
+    },
+  )?;
+
+  emit_cpi!(VoucherInitializedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    voucher: ctx.accounts.voucher.key(),
+    wallet_share: ctx.accounts.wallet_share.key(),
+    wallet: wallet_share.wallet,
+    mint: Pubkey::default(),
+    shares: wallet_share.shares,
+    cron_transaction_id: ctx.accounts.voucher.cron_transaction_id,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use tuktuk_program::{
+  cron::{
+    cpi::{accounts::InitializeCronJobV0, initialize_cron_job_v0},
+    program::Cron,
+    types::InitializeCronJobArgsV0,
+  },
+  tuktuk::program::Tuktuk,
+  TaskQueueAuthorityV0, TaskQueueV0,
+};
+
+use crate::{
+  errors::ErrorCode,
+  events::TokenInflowCronJobInitializedV0,
+  queue_authority_seeds,
+  resize_to_fit::resize_to_fit,
+  state::{FanoutV0, GlobalStateV0, TokenInflowV0},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeTokenInflowCronJobV0Args {
+  pub schedule: String,
+}
+
+/// Name of the cron job running `mint`'s claims for the fanout named `fanout_name`
+pub fn token_inflow_cron_job_name(fanout_name: &str, mint: &Pubkey) -> String {
+  format!("{fanout_name}/{mint}")
+}
+
+/// Gives a token inflow its own cron job, so its vouchers are paid on their own schedule. New
+/// vouchers are scheduled on it, existing ones are moved off the fanout's cron job with
+/// unschedule_voucher_v0 and reschedule_voucher_v0.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeTokenInflowCronJobV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub authority: Signer<'info>,
+  #[account(
+    has_one = authority,
+  )]
+  pub fanout: Box<Account<'info, FanoutV0>>,
+  #[account(
+    mut,
+    has_one = fanout,
+    constraint = token_inflow.cron_job.is_none() @ ErrorCode::InflowHasCronJob,
+  )]
+  pub token_inflow: Box<Account<'info, TokenInflowV0>>,
+
+  #[account(
+        has_one = task_queue,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+    )]
+  pub global_state: Box<Account<'info, GlobalStateV0>>,
+
+  #[account(mut)]
+  pub task_queue: Box<Account<'info, TaskQueueV0>>,
+
+  #[account(
+    seeds = [b"task_queue_authority", task_queue.key().as_ref(), queue_authority.key().as_ref()],
+    bump = task_queue_authority.bump_seed,
+    seeds::program = tuktuk_program::tuktuk::ID,
+  )]
+  pub task_queue_authority: Box<Account<'info, TaskQueueAuthorityV0>>,
+
+  #[account(
+        seeds = [b"queue_authority"],
+        bump = fanout.queue_authority_bump,
+    )]
+  /// CHECK: This is a PDA that is the authority on the task queue
+  pub queue_authority: UncheckedAccount<'info>,
+
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub user_cron_jobs: AccountInfo<'info>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub cron_job: AccountInfo<'info>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub cron_job_name_mapping: AccountInfo<'info>,
+  /// CHECK: Initialized in CPI
+  #[account(mut)]
+  pub task: AccountInfo<'info>,
+  /// CHECK: Used to write return data
+  #[account(mut)]
+  pub task_return_account_1: AccountInfo<'info>,
+  /// CHECK: Used to write return data
+  #[account(mut)]
+  pub task_return_account_2: AccountInfo<'info>,
+  pub system_program: Program<'info, System>,
+  pub tuktuk_program: Program<'info, Tuktuk>,
+  pub cron_program: Program<'info, Cron>,
+}
+
+pub fn handler(
+  ctx: Context<InitializeTokenInflowCronJobV0>,
+  args: InitializeTokenInflowCronJobV0Args,
+) -> Result<()> {
+  initialize_cron_job_v0(
+    CpiContext::new_with_signer(
+      ctx.accounts.cron_program.to_account_info(),
+      InitializeCronJobV0 {
+        payer: ctx.accounts.payer.to_account_info(),
+        queue_authority: ctx.accounts.queue_authority.to_account_info(),
+        task_queue_authority: ctx.accounts.task_queue_authority.to_account_info(),
+        authority: ctx.accounts.queue_authority.to_account_info(),
+        user_cron_jobs: ctx.accounts.user_cron_jobs.to_account_info(),
+        cron_job: ctx.accounts.cron_job.to_account_info(),
+        cron_job_name_mapping: ctx.accounts.cron_job_name_mapping.to_account_info(),
+        task_queue: ctx.accounts.task_queue.to_account_info(),
+        task: ctx.accounts.task.to_account_info(),
+        task_return_account_1: ctx.accounts.task_return_account_1.to_account_info(),
+        task_return_account_2: ctx.accounts.task_return_account_2.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        tuktuk_program: ctx.accounts.tuktuk_program.to_account_info(),
+      },
+      &[queue_authority_seeds!(ctx.accounts.fanout)],
+    ),
+    InitializeCronJobArgsV0 {
+      name: token_inflow_cron_job_name(&ctx.accounts.fanout.name, &ctx.accounts.token_inflow.mint),
+      schedule: args.schedule.clone(),
+      free_tasks_per_transaction: 0,
+      num_tasks_per_queue_call: 5,
+    },
+  )?;
+
+  // Inflows allocated before `cron_job` existed have no room for it
+  ctx.accounts.token_inflow.cron_job = Some(ctx.accounts.cron_job.key());
+  resize_to_fit(
+    &ctx.accounts.payer.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.token_inflow,
+  )?;
+
+  emit_cpi!(TokenInflowCronJobInitializedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    token_inflow: ctx.accounts.token_inflow.key(),
+    mint: ctx.accounts.token_inflow.mint,
+    cron_job: ctx.accounts.cron_job.key(),
+    schedule: args.schedule,
+  });
+
+  Ok(())
+}
@@ -1,7 +1,7 @@
 use anchor_lang::{prelude::*, solana_program::instruction::Instruction, InstructionData};
 use anchor_spl::{
-  associated_token::{get_associated_token_address, AssociatedToken},
-  token::{spl_token, Mint, Token, TokenAccount},
+  associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+  token_interface::{Mint, TokenAccount, TokenInterface},
 };
 use tuktuk_program::{
   compile_transaction,
@@ -13,7 +13,11 @@ use tuktuk_program::{
   },
 };
 
+use super::ata_payer_key;
 use crate::{
+  errors::ErrorCode,
+  events::{emit_optional_cpi, event_authority_key, VoucherInitializedV0},
+  program::WalletFanout,
   queue_authority_seeds,
   state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
 };
@@ -22,10 +26,7 @@ use crate::{
 pub struct InitializeVoucherV0<'info> {
   #[account(mut)]
   pub payer: Signer<'info>,
-  #[account(
-    mut,
-    has_one = cron_job
-  )]
+  #[account(mut)]
   pub fanout: Account<'info, FanoutV0>,
 
   #[account(
@@ -42,8 +43,9 @@ pub struct InitializeVoucherV0<'info> {
     payer = payer,
     associated_token::mint = mint,
     associated_token::authority = fanout,
+    associated_token::token_program = token_program,
   )]
-  pub fanout_token_account: Account<'info, TokenAccount>,
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
         init,
@@ -59,12 +61,11 @@ pub struct InitializeVoucherV0<'info> {
     )]
   pub voucher: Account<'info, VoucherV0>,
 
-  #[account(
-        has_one = fanout,
-    )]
-  pub wallet_share: Account<'info, WalletShareV0>,
+  /// CHECK: Loaded with WalletShareV0::load, which also reads wallet shares that aren't migrated
+  #[account(owner = crate::ID)]
+  pub wallet_share: AccountInfo<'info>,
 
-  pub mint: Account<'info, Mint>,
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
   #[account(
     seeds = [b"queue_authority"],
     bump = fanout.queue_authority_bump,
@@ -72,18 +73,37 @@ pub struct InitializeVoucherV0<'info> {
   /// CHECK: Used for signing
   pub authority: AccountInfo<'info>,
 
-  #[account(mut, has_one = authority)]
+  #[account(
+    mut,
+    has_one = authority,
+    constraint = cron_job.key() == token_inflow.voucher_cron_job(&fanout) @ ErrorCode::InvalidCronJob,
+  )]
   pub cron_job: Box<Account<'info, CronJobV0>>,
   #[account(mut)]
   /// CHECK: Init in CPI
   pub cron_job_transaction: AccountInfo<'info>,
   pub cron_program: Program<'info, Cron>,
   pub system_program: Program<'info, System>,
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
 pub fn handler(ctx: Context<InitializeVoucherV0>) -> Result<()> {
+  let wallet_share = WalletShareV0::load(&ctx.accounts.wallet_share)?;
+  require_keys_eq!(
+    wallet_share.fanout,
+    ctx.accounts.fanout.key(),
+    anchor_lang::error::ErrorCode::ConstraintHasOne
+  );
+
   let fanout = &mut ctx.accounts.fanout;
 
   // Initialize token inflow if needed
@@ -96,10 +116,20 @@ pub fn handler(ctx: Context<InitializeVoucherV0>) -> Result<()> {
       bump: ctx.bumps.token_inflow,
       rent_refund: ctx.accounts.payer.key(),
       num_vouchers: 0,
+      unrecovered_fees: 0,
+      total_shares: fanout.total_shares,
+      acc_per_share: 0,
+      accumulator_initialized: true,
+      treasury_amount: 0,
+      paused: false,
+      min_payout: 0,
+      cron_job: None,
+      retained_amount: 0,
     });
     // Increment fanout's inflow count
     fanout.num_inflows = fanout.num_inflows.checked_add(1).unwrap();
   }
+  ctx.accounts.token_inflow.initialize_accumulator(fanout);
 
   // After initializing the token_inflow
   ctx.accounts.token_inflow.num_vouchers = ctx
@@ -112,15 +142,29 @@ pub fn handler(ctx: Context<InitializeVoucherV0>) -> Result<()> {
   let cron_transaction_id = fanout.get_next_cron_transaction_id();
 
   ctx.accounts.voucher.set_inner(VoucherV0 {
-    wallet: ctx.accounts.wallet_share.wallet,
+    wallet: wallet_share.wallet,
     fanout: fanout.key(),
     mint: ctx.accounts.mint.key(),
     cron_transaction_id,
     last_claimed_inflow: ctx.accounts.token_inflow.total_inflow,
     total_dust: 0,
     wallet_share: ctx.accounts.wallet_share.key(),
-    shares: ctx.accounts.wallet_share.shares,
+    shares: wallet_share.shares,
     rent_refund: ctx.accounts.payer.key(),
+    total_gross_claimed: 0,
+    total_net_claimed: 0,
+    pending_amount: 0,
+    reward_debt: (wallet_share.shares as u128)
+      .checked_mul(ctx.accounts.token_inflow.acc_per_share)
+      .unwrap(),
+    accumulator_initialized: true,
+    total_claimed: 0,
+    claim_count: 0,
+    last_claim_unix_ts: 0,
+    last_claim_slot: 0,
+    escrowed_amount: 0,
+    pending_since_unix_ts: 0,
+    min_payout: 0,
   });
 
   let ixs = vec![Instruction {
@@ -131,18 +175,23 @@ pub fn handler(ctx: Context<InitializeVoucherV0>) -> Result<()> {
       voucher: ctx.accounts.voucher.key(),
       mint: ctx.accounts.mint.key(),
       fanout_token_account: ctx.accounts.fanout_token_account.key(),
-      receiver_token_account: get_associated_token_address(
-        &ctx.accounts.wallet_share.wallet,
+      receiver_token_account: get_associated_token_address_with_program_id(
+        &wallet_share.wallet,
         &ctx.accounts.mint.key(),
+        &ctx.accounts.token_program.key(),
       ),
-      token_program: spl_token::ID,
+      token_program: ctx.accounts.token_program.key(),
       wallet_share: ctx.accounts.wallet_share.key(),
-      wallet: ctx.accounts.wallet_share.wallet,
+      wallet: wallet_share.wallet,
       cron_job: ctx.accounts.cron_job.key(),
       cron_job_transaction: ctx.accounts.cron_job_transaction.key(),
       queue_authority: ctx.accounts.authority.key(),
       cron_program: ctx.accounts.cron_program.key(),
       system_program: ctx.accounts.system_program.key(),
+      ata_payer: Some(ata_payer_key(&fanout.key())),
+      associated_token_program: Some(ctx.accounts.associated_token_program.key()),
+      event_authority: Some(event_authority_key()),
+      program: Some(crate::ID),
     }
     .to_account_metas(None),
     data: crate::instruction::ClaimV0.data(),
@@ -169,5 +218,19 @@ pub fn handler(ctx: Context<InitializeVoucherV0>) -> Result<()> {
     },
   )?;
 
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    VoucherInitializedV0 {
+      fanout: ctx.accounts.fanout.key(),
+      voucher: ctx.accounts.voucher.key(),
+      wallet_share: ctx.accounts.wallet_share.key(),
+      wallet: wallet_share.wallet,
+      mint: ctx.accounts.mint.key(),
+      shares: wallet_share.shares,
+      cron_transaction_id: ctx.accounts.voucher.cron_transaction_id,
+    },
+  )?;
+
   Ok(())
 }
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  errors::ErrorCode,
+  state::{FanoutV0, TokenInflowV0, VoucherV0},
+};
+
+/// Remaining accounts are vouchers of `token_inflow` to convert along with it. Vouchers left out
+/// are converted the first time they are claimed. Vouchers must already be resized by
+/// migrate_voucher_v0.
+#[derive(Accounts)]
+pub struct MigrateAccumulatorV0<'info> {
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    has_one = fanout,
+  )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+}
+
+pub fn handler<'info>(
+  ctx: Context<'_, '_, 'info, 'info, MigrateAccumulatorV0<'info>>,
+) -> Result<()> {
+  ctx
+    .accounts
+    .token_inflow
+    .initialize_accumulator(&ctx.accounts.fanout);
+
+  for account in ctx.remaining_accounts {
+    let mut voucher = Account::<VoucherV0>::try_from(account)?;
+    require_keys_eq!(
+      voucher.fanout,
+      ctx.accounts.fanout.key(),
+      ErrorCode::InvalidInflowAccounts
+    );
+    require_keys_eq!(
+      voucher.mint,
+      ctx.accounts.token_inflow.mint,
+      ErrorCode::InvalidInflowAccounts
+    );
+    voucher.initialize_accumulator(&ctx.accounts.token_inflow);
+    voucher.exit(&crate::ID)?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,198 @@
+use anchor_lang::{
+  prelude::*,
+  system_program::{transfer, Transfer},
+};
+use tuktuk_program::{
+  cron::{
+    accounts::CronJobV0,
+    cpi::{
+      accounts::{CloseCronJobV0, InitializeCronJobV0},
+      close_cron_job_v0, initialize_cron_job_v0,
+    },
+    program::Cron,
+    types::InitializeCronJobArgsV0,
+  },
+  tuktuk::program::Tuktuk,
+  TaskQueueAuthorityV0, TaskQueueV0,
+};
+
+use crate::{
+  errors::ErrorCode,
+  events::FanoutCronJobMigratedV0,
+  queue_authority_seeds,
+  state::{FanoutV0, GlobalStateV0},
+};
+
+/// Whether the fanout's cron job has to be recreated, because it runs on a task queue that is no
+/// longer the global one or its schedule was changed
+pub fn cron_job_needs_migration(
+  fanout: &FanoutV0,
+  cron_job: &CronJobV0,
+  global_state: &GlobalStateV0,
+) -> bool {
+  fanout.pending_schedule.is_some() || cron_job.task_queue != global_state.task_queue
+}
+
+/// Replaces the fanout's cron job once every voucher has been unscheduled from it. Vouchers keep their
+/// `cron_transaction_id` and are scheduled on the new cron job with reschedule_voucher_v0. The old
+/// cron job's rent goes back to the fanout authority and the SOL funding its tasks moves to the new one.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateFanoutCronJobV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+        has_one = task_queue,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+    )]
+  pub global_state: Box<Account<'info, GlobalStateV0>>,
+
+  #[account(
+        mut,
+        has_one = authority,
+        has_one = cron_job,
+    )]
+  pub fanout: Box<Account<'info, FanoutV0>>,
+
+  #[account(
+        mut,
+        constraint = cron_job_needs_migration(&fanout, &cron_job, &global_state) @ ErrorCode::CronJobUpToDate,
+        constraint = cron_job.num_transactions == 0 @ ErrorCode::CronJobHasTransactions,
+    )]
+  pub cron_job: Box<Account<'info, CronJobV0>>,
+  /// CHECK: Used to write return data for the old cron job, checked in CPI
+  #[account(mut)]
+  pub old_task_return_account_1: AccountInfo<'info>,
+  /// CHECK: Used to write return data for the old cron job, checked in CPI
+  #[account(mut)]
+  pub old_task_return_account_2: AccountInfo<'info>,
+
+  #[account(mut)]
+  pub task_queue: Box<Account<'info, TaskQueueV0>>,
+
+  #[account(
+    seeds = [b"task_queue_authority", task_queue.key().as_ref(), queue_authority.key().as_ref()],
+    bump = task_queue_authority.bump_seed,
+    seeds::program = tuktuk_program::tuktuk::ID,
+  )]
+  pub task_queue_authority: Box<Account<'info, TaskQueueAuthorityV0>>,
+
+  #[account(
+        seeds = [b"queue_authority"],
+        bump = fanout.queue_authority_bump,
+    )]
+  /// CHECK: This is a PDA that is the authority on the task queue
+  pub queue_authority: UncheckedAccount<'info>,
+
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub user_cron_jobs: AccountInfo<'info>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub new_cron_job: AccountInfo<'info>,
+  #[account(mut)]
+  /// CHECK: Used in CPI
+  pub cron_job_name_mapping: AccountInfo<'info>,
+  /// CHECK: Initialized in CPI
+  #[account(mut)]
+  pub task: AccountInfo<'info>,
+  /// CHECK: Used to write return data
+  #[account(mut)]
+  pub task_return_account_1: AccountInfo<'info>,
+  /// CHECK: Used to write return data
+  #[account(mut)]
+  pub task_return_account_2: AccountInfo<'info>,
+  pub system_program: Program<'info, System>,
+  pub tuktuk_program: Program<'info, Tuktuk>,
+  pub cron_program: Program<'info, Cron>,
+}
+
+pub fn handler(ctx: Context<MigrateFanoutCronJobV0>) -> Result<()> {
+  let name = ctx.accounts.cron_job.name.clone();
+  let schedule = ctx
+    .accounts
+    .fanout
+    .pending_schedule
+    .take()
+    .unwrap_or_else(|| ctx.accounts.cron_job.schedule.clone());
+  let free_tasks_per_transaction = ctx.accounts.cron_job.free_tasks_per_transaction;
+  let num_tasks_per_queue_call = ctx.accounts.cron_job.num_tasks_per_queue_call;
+  let old_cron_job = ctx.accounts.cron_job.to_account_info();
+  let funding = old_cron_job
+    .lamports()
+    .saturating_sub(Rent::get()?.minimum_balance(old_cron_job.data_len()));
+
+  // Closing the old cron job frees its name so the new one can take it over
+  close_cron_job_v0(CpiContext::new_with_signer(
+    ctx.accounts.cron_program.to_account_info(),
+    CloseCronJobV0 {
+      rent_refund: ctx.accounts.authority.to_account_info(),
+      authority: ctx.accounts.queue_authority.to_account_info(),
+      user_cron_jobs: ctx.accounts.user_cron_jobs.to_account_info(),
+      cron_job: ctx.accounts.cron_job.to_account_info(),
+      cron_job_name_mapping: ctx.accounts.cron_job_name_mapping.to_account_info(),
+      system_program: ctx.accounts.system_program.to_account_info(),
+      task_return_account_1: ctx.accounts.old_task_return_account_1.to_account_info(),
+      task_return_account_2: ctx.accounts.old_task_return_account_2.to_account_info(),
+    },
+    &[queue_authority_seeds!(ctx.accounts.fanout)],
+  ))?;
+
+  initialize_cron_job_v0(
+    CpiContext::new_with_signer(
+      ctx.accounts.cron_program.to_account_info(),
+      InitializeCronJobV0 {
+        payer: ctx.accounts.payer.to_account_info(),
+        queue_authority: ctx.accounts.queue_authority.to_account_info(),
+        task_queue_authority: ctx.accounts.task_queue_authority.to_account_info(),
+        authority: ctx.accounts.queue_authority.to_account_info(),
+        user_cron_jobs: ctx.accounts.user_cron_jobs.to_account_info(),
+        cron_job: ctx.accounts.new_cron_job.to_account_info(),
+        cron_job_name_mapping: ctx.accounts.cron_job_name_mapping.to_account_info(),
+        task_queue: ctx.accounts.task_queue.to_account_info(),
+        task: ctx.accounts.task.to_account_info(),
+        task_return_account_1: ctx.accounts.task_return_account_1.to_account_info(),
+        task_return_account_2: ctx.accounts.task_return_account_2.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        tuktuk_program: ctx.accounts.tuktuk_program.to_account_info(),
+      },
+      &[queue_authority_seeds!(ctx.accounts.fanout)],
+    ),
+    InitializeCronJobArgsV0 {
+      name,
+      schedule: schedule.clone(),
+      free_tasks_per_transaction,
+      num_tasks_per_queue_call,
+    },
+  )?;
+
+  if funding > 0 {
+    transfer(
+      CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        Transfer {
+          from: ctx.accounts.authority.to_account_info(),
+          to: ctx.accounts.new_cron_job.to_account_info(),
+        },
+      ),
+      funding,
+    )?;
+  }
+
+  ctx.accounts.fanout.cron_job = ctx.accounts.new_cron_job.key();
+
+  emit_cpi!(FanoutCronJobMigratedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    old_cron_job: ctx.accounts.cron_job.key(),
+    new_cron_job: ctx.accounts.new_cron_job.key(),
+    task_queue: ctx.accounts.task_queue.key(),
+    schedule,
+    funding,
+  });
+
+  Ok(())
+}
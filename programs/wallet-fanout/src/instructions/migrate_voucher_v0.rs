@@ -0,0 +1,60 @@
+use anchor_lang::{
+  prelude::*,
+  solana_program::{program::invoke, system_instruction},
+  Discriminator,
+};
+
+use crate::{errors::ErrorCode, state::VoucherV0};
+
+/// Grows a voucher created before its transfer fee totals, pending and escrowed amounts,
+/// accumulator, claim statistics, claim window or minimum payout existed to the current layout.
+/// Those vouchers can't be deserialized until they are migrated, so their claims fail until then.
+///
+/// After upgrading a fanout from that layout:
+/// 1. Migrate every voucher with this instruction. Anyone can pay for it, and a voucher's cron claim
+///    works again once it is migrated.
+/// 2. Optionally convert each inflow and its vouchers with migrate_accumulator_v0. Vouchers left out
+///    are converted the first time they are claimed.
+/// 3. Migrate wallet shares with migrate_wallet_share_v0 before they are updated, suspended or
+///    closed.
+#[derive(Accounts)]
+pub struct MigrateVoucherV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  /// CHECK: Too small to deserialize, the discriminator is checked in the handler
+  #[account(
+    mut,
+    owner = crate::ID,
+  )]
+  pub voucher: AccountInfo<'info>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateVoucherV0>) -> Result<()> {
+  let voucher = &ctx.accounts.voucher;
+  require!(
+    voucher
+      .try_borrow_data()?
+      .starts_with(VoucherV0::DISCRIMINATOR),
+    anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+  );
+  let new_size = 8 + 60 + std::mem::size_of::<VoucherV0>();
+  require_gt!(new_size, voucher.data_len(), ErrorCode::VoucherUpToDate);
+
+  let lamports_diff = Rent::get()?
+    .minimum_balance(new_size)
+    .saturating_sub(voucher.lamports());
+  if lamports_diff > 0 {
+    invoke(
+      &system_instruction::transfer(ctx.accounts.payer.key, voucher.key, lamports_diff),
+      &[
+        ctx.accounts.payer.to_account_info(),
+        voucher.clone(),
+        ctx.accounts.system_program.to_account_info(),
+      ],
+    )?;
+  }
+  voucher.realloc(new_size, true)?;
+
+  Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::{
+  prelude::*,
+  solana_program::{program::invoke, system_instruction},
+  Discriminator,
+};
+
+use crate::{errors::ErrorCode, state::WalletShareV0};
+
+/// Grows a wallet share created before it could be suspended to the current layout. Claims and
+/// voucher instructions read those wallet shares as not suspended, but they have to be migrated
+/// before they can be updated, suspended or closed.
+#[derive(Accounts)]
+pub struct MigrateWalletShareV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  /// CHECK: Too small to deserialize, the discriminator is checked in the handler
+  #[account(
+    mut,
+    owner = crate::ID,
+  )]
+  pub wallet_share: AccountInfo<'info>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateWalletShareV0>) -> Result<()> {
+  let wallet_share = &ctx.accounts.wallet_share;
+  require!(
+    wallet_share
+      .try_borrow_data()?
+      .starts_with(WalletShareV0::DISCRIMINATOR),
+    anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+  );
+  let new_size = 8 + 60 + std::mem::size_of::<WalletShareV0>();
+  require_gt!(
+    new_size,
+    wallet_share.data_len(),
+    ErrorCode::WalletShareUpToDate
+  );
+
+  let lamports_diff = Rent::get()?
+    .minimum_balance(new_size)
+    .saturating_sub(wallet_share.lamports());
+  if lamports_diff > 0 {
+    invoke(
+      &system_instruction::transfer(ctx.accounts.payer.key, wallet_share.key, lamports_diff),
+      &[
+        ctx.accounts.payer.to_account_info(),
+        wallet_share.clone(),
+        ctx.accounts.system_program.to_account_info(),
+      ],
+    )?;
+  }
+  wallet_share.realloc(new_size, true)?;
+
+  Ok(())
+}
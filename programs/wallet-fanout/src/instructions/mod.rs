@@ -1,21 +1,91 @@
+pub mod accept_fanout_authority_v0;
+pub mod accept_global_state_authority_v0;
+pub mod claim_sol_v0;
+pub mod claim_to_v0;
+pub mod claim_treasury_sol_v0;
+pub mod claim_treasury_v0;
 pub mod claim_v0;
 pub mod close_fanout_v0;
+pub mod close_sol_inflow_v0;
+pub mod close_sol_voucher_v0;
 pub mod close_token_inflow_v0;
 pub mod close_voucher_v0;
 pub mod close_wallet_share_v0;
+pub mod forfeit_voucher_v0;
 pub mod initialize_fanout_v0;
 pub mod initialize_global_state_v0;
+pub mod initialize_sol_voucher_v0;
+pub mod initialize_token_inflow_cron_job_v0;
 pub mod initialize_voucher_v0;
+pub mod migrate_accumulator_v0;
+pub mod migrate_fanout_cron_job_v0;
+pub mod migrate_voucher_v0;
+pub mod migrate_wallet_share_v0;
+pub mod pause_fanout_v0;
+pub mod preview_claim_v0;
+pub mod propose_fanout_authority_v0;
 pub mod requeue_fanout_task_v0;
+pub mod reschedule_sol_voucher_v0;
+pub mod reschedule_voucher_v0;
+pub mod resume_fanout_v0;
+pub mod rotate_sol_voucher_wallet_v0;
+pub mod rotate_voucher_wallet_v0;
+pub mod suspend_wallet_share_v0;
+pub mod unschedule_voucher_v0;
+pub mod unsuspend_wallet_share_v0;
+pub mod update_fanout_schedule_v0;
+pub mod update_fanout_v0;
+pub mod update_global_state_v0;
+pub mod update_token_inflow_v0;
+pub mod update_total_shares_v0;
+pub mod update_unissued_share_policy_v0;
+pub mod update_voucher_v0;
 pub mod update_wallet_share_v0;
+pub mod withdraw_pending_sol_v0;
+pub mod withdraw_pending_v0;
 
+pub use accept_fanout_authority_v0::*;
+pub use accept_global_state_authority_v0::*;
+pub use claim_sol_v0::*;
+pub use claim_to_v0::*;
+pub use claim_treasury_sol_v0::*;
+pub use claim_treasury_v0::*;
 pub use claim_v0::*;
 pub use close_fanout_v0::*;
+pub use close_sol_inflow_v0::*;
+pub use close_sol_voucher_v0::*;
 pub use close_token_inflow_v0::*;
 pub use close_voucher_v0::*;
 pub use close_wallet_share_v0::*;
+pub use forfeit_voucher_v0::*;
 pub use initialize_fanout_v0::*;
 pub use initialize_global_state_v0::*;
+pub use initialize_sol_voucher_v0::*;
+pub use initialize_token_inflow_cron_job_v0::*;
 pub use initialize_voucher_v0::*;
+pub use migrate_accumulator_v0::*;
+pub use migrate_fanout_cron_job_v0::*;
+pub use migrate_voucher_v0::*;
+pub use migrate_wallet_share_v0::*;
+pub use pause_fanout_v0::*;
+pub use preview_claim_v0::*;
+pub use propose_fanout_authority_v0::*;
 pub use requeue_fanout_task_v0::*;
+pub use reschedule_sol_voucher_v0::*;
+pub use reschedule_voucher_v0::*;
+pub use resume_fanout_v0::*;
+pub use rotate_sol_voucher_wallet_v0::*;
+pub use rotate_voucher_wallet_v0::*;
+pub use suspend_wallet_share_v0::*;
+pub use unschedule_voucher_v0::*;
+pub use unsuspend_wallet_share_v0::*;
+pub use update_fanout_schedule_v0::*;
+pub use update_fanout_v0::*;
+pub use update_global_state_v0::*;
+pub use update_token_inflow_v0::*;
+pub use update_total_shares_v0::*;
+pub use update_unissued_share_policy_v0::*;
+pub use update_voucher_v0::*;
 pub use update_wallet_share_v0::*;
+pub use withdraw_pending_sol_v0::*;
+pub use withdraw_pending_v0::*;
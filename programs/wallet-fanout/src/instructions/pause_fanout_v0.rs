@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  events::FanoutPausedV0,
+  resize_to_fit::resize_to_fit,
+  state::{FanoutV0, TokenInflowV0},
+};
+
+/// Pauses payouts of the whole fanout, or only of `token_inflow` when it is passed
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PauseFanoutV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    has_one = fanout,
+  )]
+  pub token_inflow: Option<Account<'info, TokenInflowV0>>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<PauseFanoutV0>) -> Result<()> {
+  let mint = match &mut ctx.accounts.token_inflow {
+    Some(token_inflow) => {
+      token_inflow.paused = true;
+      Some(token_inflow.mint)
+    }
+    None => {
+      ctx.accounts.fanout.paused = true;
+      None
+    }
+  };
+
+  // Fanouts created before new fields were added may need more space
+  resize_to_fit(
+    &ctx.accounts.payer.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.fanout,
+  )?;
+
+  emit_cpi!(FanoutPausedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    mint,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use super::can_receive_lamports;
+use crate::{
+  settle::inflow_balance,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ClaimPreviewV0 {
+  /// Everything a claim would pay out right now, including the pending amount. Nothing while the
+  /// member is suspended or, for SOL, while their wallet can't receive it
+  pub claimable: u64,
+  /// Amount owed that couldn't be delivered, including what a claim would add to it
+  pub pending_amount: u64,
+  /// Amount held back while the member is suspended, including what a claim would withhold
+  pub escrowed_amount: u64,
+  /// Amount owed below one token that would carry over to the next claim. Scaled by `TWELVE_PREC`
+  pub dust: u128,
+  /// Inflow that arrived since the inflow was last updated
+  pub new_inflow: u64,
+  /// The inflow's total after the update
+  pub total_inflow: u64,
+  /// Whether payouts are paused, in which case a claim pays nothing until resumed
+  pub paused: bool,
+  /// When the pending amount can be forfeited if it still hasn't been delivered
+  pub pending_expires_unix_ts: Option<i64>,
+  /// Whether cron claims skip the voucher because `claimable` is below the minimum payout
+  pub below_min_payout: bool,
+}
+
+/// Computes what claiming `voucher` would pay without changing any account, so it can be simulated.
+/// The preview is returned through return data.
+#[derive(Accounts)]
+pub struct PreviewClaimV0<'info> {
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+        seeds = [b"token_inflow", fanout.key().as_ref(), voucher.mint.as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+  #[account(
+        has_one = fanout,
+        has_one = wallet_share,
+        has_one = wallet,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+  /// CHECK: Checked by has_one, may already be closed
+  pub wallet_share: AccountInfo<'info>,
+  /// CHECK: Checked by has_one, SOL payouts depend on its balance
+  pub wallet: AccountInfo<'info>,
+  /// CHECK: The fanout's token account for the voucher's mint, or the fanout itself for SOL
+  pub balance_account: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<PreviewClaimV0>) -> Result<ClaimPreviewV0> {
+  let balance = inflow_balance(
+    &ctx.accounts.fanout.to_account_info(),
+    &ctx.accounts.token_inflow,
+    &ctx.accounts.balance_account,
+  )?;
+
+  let mut token_inflow = (*ctx.accounts.token_inflow).clone();
+  let mut voucher = (*ctx.accounts.voucher).clone();
+  token_inflow.update_total_inflow(balance, &ctx.accounts.fanout);
+  let below_min_payout = voucher.below_min_payout(&token_inflow);
+  let amount = voucher.calculate_claim(&token_inflow);
+  let owed = amount.checked_add(voucher.pending_amount).unwrap();
+
+  // Mirrors where claim_v0 and claim_sol_v0 would put what is newly owed
+  let (claimable, pending_amount, escrowed_amount) =
+    if WalletShareV0::is_suspended(&ctx.accounts.wallet_share)? {
+      (
+        0,
+        voucher.pending_amount,
+        voucher.escrowed_amount.checked_add(amount).unwrap(),
+      )
+    } else if token_inflow.is_native() && !can_receive_lamports(&ctx.accounts.wallet, owed)? {
+      (
+        0,
+        voucher.pending_amount.checked_add(amount).unwrap(),
+        voucher.escrowed_amount,
+      )
+    } else {
+      (owed, voucher.pending_amount, voucher.escrowed_amount)
+    };
+
+  Ok(ClaimPreviewV0 {
+    claimable,
+    pending_amount,
+    escrowed_amount,
+    dust: voucher.dust(&token_inflow),
+    new_inflow: token_inflow
+      .total_inflow
+      .checked_sub(ctx.accounts.token_inflow.total_inflow)
+      .unwrap(),
+    total_inflow: token_inflow.total_inflow,
+    paused: token_inflow.is_paused(&ctx.accounts.fanout),
+    pending_expires_unix_ts: voucher.pending_expires_unix_ts(&ctx.accounts.fanout),
+    below_min_payout,
+  })
+}
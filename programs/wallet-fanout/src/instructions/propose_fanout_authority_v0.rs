@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::FanoutAuthorityProposedV0, resize_to_fit::resize_to_fit, state::FanoutV0};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposeFanoutAuthorityV0Args {
+  /// The authority to hand the fanout to, or None to cancel a pending proposal
+  pub pending_authority: Option<Pubkey>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeFanoutAuthorityV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+  ctx: Context<ProposeFanoutAuthorityV0>,
+  args: ProposeFanoutAuthorityV0Args,
+) -> Result<()> {
+  ctx.accounts.fanout.pending_authority = args.pending_authority;
+
+  // Fanouts created before pending_authority was added may need more space
+  resize_to_fit(
+    &ctx.accounts.payer.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.fanout,
+  )?;
+
+  emit_cpi!(FanoutAuthorityProposedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    authority: ctx.accounts.authority.key(),
+    pending_authority: args.pending_authority,
+  });
+
+  Ok(())
+}
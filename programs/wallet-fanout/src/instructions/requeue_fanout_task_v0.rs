@@ -10,13 +10,25 @@ use tuktuk_program::{
   TaskQueueAuthorityV0, TaskQueueV0,
 };
 
-use crate::{FanoutV0, GlobalStateV0};
+use crate::{
+  errors::ErrorCode,
+  events::{emit_optional_cpi, FanoutTaskRequeuedV0},
+  program::WalletFanout,
+  FanoutV0, GlobalStateV0, TokenInflowV0,
+};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct RequeueFanoutTaskArgsV0 {
   pub task_id: u16,
 }
 
+/// The cron job being requeued: the inflow's own when one is passed, otherwise the fanout's
+fn requeued_cron_job(fanout: &FanoutV0, token_inflow: Option<&TokenInflowV0>) -> Pubkey {
+  token_inflow.map_or(fanout.cron_job, |token_inflow| {
+    token_inflow.voucher_cron_job(fanout)
+  })
+}
+
 #[derive(Accounts)]
 pub struct RequeueFanoutTaskV0<'info> {
   #[account(mut)]
@@ -27,9 +39,6 @@ pub struct RequeueFanoutTaskV0<'info> {
     )]
   pub global_state: Account<'info, GlobalStateV0>,
 
-  #[account(
-        has_one = cron_job,
-    )]
   pub fanout: Account<'info, FanoutV0>,
 
   #[account(mut)]
@@ -50,7 +59,8 @@ pub struct RequeueFanoutTaskV0<'info> {
   pub queue_authority: UncheckedAccount<'info>,
 
   #[account(
-    constraint = cron_job.next_schedule_task == Pubkey::default() || payer.key() == global_state.authority
+    constraint = cron_job.next_schedule_task == Pubkey::default() || payer.key() == global_state.authority,
+    constraint = cron_job.key() == requeued_cron_job(&fanout, token_inflow.as_deref()) @ ErrorCode::InvalidCronJob,
   )]
   pub cron_job: Account<'info, CronJobV0>,
 
@@ -66,6 +76,19 @@ pub struct RequeueFanoutTaskV0<'info> {
   pub tuktuk_program: Program<'info, Tuktuk>,
   pub cron_program: Program<'info, Cron>,
   pub system_program: Program<'info, System>,
+  /// Requeues this inflow's own cron job instead of the fanout's
+  #[account(
+    has_one = fanout,
+  )]
+  pub token_inflow: Option<Account<'info, TokenInflowV0>>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
 pub fn handler(ctx: Context<RequeueFanoutTaskV0>, args: RequeueFanoutTaskArgsV0) -> Result<()> {
@@ -92,5 +115,15 @@ pub fn handler(ctx: Context<RequeueFanoutTaskV0>, args: RequeueFanoutTaskArgsV0)
     },
   )?;
 
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    FanoutTaskRequeuedV0 {
+      fanout: ctx.accounts.fanout.key(),
+      cron_job: ctx.accounts.cron_job.key(),
+      task_id: args.task_id,
+    },
+  )?;
+
   Ok(())
 }
@@ -0,0 +1,101 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, InstructionData};
+use tuktuk_program::{
+  compile_transaction,
+  cron::{
+    accounts::CronJobV0,
+    cpi::{accounts::AddCronTransactionV0, add_cron_transaction_v0},
+    program::Cron,
+    types::AddCronTransactionArgsV0,
+  },
+};
+
+use crate::{
+  errors::ErrorCode,
+  queue_authority_seeds,
+  state::{FanoutV0, TokenInflowV0, VoucherV0},
+};
+
+/// Schedules an unscheduled SOL voucher's claim on the cron job that runs its inflow, paying the
+/// wallet the voucher already pays. Nothing is settled, so this works while the fanout is paused.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RescheduleSolVoucherV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    constraint = cron_job.key() == token_inflow.voucher_cron_job(&fanout) @ ErrorCode::InvalidCronJob,
+  )]
+  pub cron_job: Box<Account<'info, CronJobV0>>,
+  #[account(
+    mut,
+    seeds = [b"cron_job_transaction", cron_job.key().as_ref(), &voucher.cron_transaction_id.to_le_bytes()[..]],
+    bump,
+    seeds::program = tuktuk_program::cron::ID,
+    constraint = cron_job_transaction.data_is_empty() @ ErrorCode::VoucherScheduled,
+  )]
+  /// CHECK: Init in CPI
+  pub cron_job_transaction: AccountInfo<'info>,
+  #[account(
+    seeds = [b"queue_authority"],
+    bump = fanout.queue_authority_bump,
+  )]
+  /// CHECK: Used for signing
+  pub queue_authority: AccountInfo<'info>,
+
+  #[account(
+        seeds = [b"token_inflow", fanout.key().as_ref(), Pubkey::default().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        has_one = fanout,
+        constraint = voucher.mint == token_inflow.mint,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+
+  pub cron_program: Program<'info, Cron>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RescheduleSolVoucherV0>) -> Result<()> {
+  let ixs = vec![Instruction {
+    program_id: crate::ID,
+    accounts: crate::accounts::ClaimSolV0 {
+      fanout: ctx.accounts.fanout.key(),
+      token_inflow: ctx.accounts.token_inflow.key(),
+      voucher: ctx.accounts.voucher.key(),
+      wallet_share: ctx.accounts.voucher.wallet_share,
+      wallet: ctx.accounts.voucher.wallet,
+      event_authority: Some(ctx.accounts.event_authority.key()),
+      program: Some(crate::ID),
+    }
+    .to_account_metas(None),
+    data: crate::instruction::ClaimSolV0.data(),
+  }];
+  let (compiled_tx, _) = compile_transaction(ixs, vec![])?;
+
+  add_cron_transaction_v0(
+    CpiContext::new_with_signer(
+      ctx.accounts.cron_program.to_account_info(),
+      AddCronTransactionV0 {
+        cron_job: ctx.accounts.cron_job.to_account_info(),
+        cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
+        payer: ctx.accounts.payer.to_account_info(),
+        authority: ctx.accounts.queue_authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+      },
+      &[queue_authority_seeds!(ctx.accounts.fanout)],
+    ),
+    AddCronTransactionArgsV0 {
+      index: ctx.accounts.voucher.cron_transaction_id,
+      transaction_source: tuktuk_program::cron::types::TransactionSourceV0::CompiledV0(
+        compiled_tx.into(),
+      ),
+    },
+  )?;
+
+  Ok(())
+}
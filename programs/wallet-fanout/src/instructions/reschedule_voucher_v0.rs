@@ -0,0 +1,131 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, InstructionData};
+use anchor_spl::{
+  associated_token::{self, get_associated_token_address_with_program_id},
+  token_interface::{Mint, TokenInterface},
+};
+use tuktuk_program::{
+  compile_transaction,
+  cron::{
+    accounts::CronJobV0,
+    cpi::{accounts::AddCronTransactionV0, add_cron_transaction_v0},
+    program::Cron,
+    types::AddCronTransactionArgsV0,
+  },
+};
+
+use super::ata_payer_key;
+use crate::{
+  errors::ErrorCode,
+  queue_authority_seeds,
+  state::{FanoutV0, TokenInflowV0, VoucherV0},
+};
+
+/// Schedules an unscheduled voucher's claim on the cron job that runs its inflow, paying the wallet
+/// the voucher already pays. Unlike rotate_voucher_wallet_v0 nothing is settled, so this works while
+/// the fanout is paused or the wallet can't receive.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RescheduleVoucherV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    constraint = cron_job.key() == token_inflow.voucher_cron_job(&fanout) @ ErrorCode::InvalidCronJob,
+  )]
+  pub cron_job: Box<Account<'info, CronJobV0>>,
+  #[account(
+    mut,
+    seeds = [b"cron_job_transaction", cron_job.key().as_ref(), &voucher.cron_transaction_id.to_le_bytes()[..]],
+    bump,
+    seeds::program = tuktuk_program::cron::ID,
+    constraint = cron_job_transaction.data_is_empty() @ ErrorCode::VoucherScheduled,
+  )]
+  /// CHECK: Init in CPI
+  pub cron_job_transaction: AccountInfo<'info>,
+  #[account(
+    seeds = [b"queue_authority"],
+    bump = fanout.queue_authority_bump,
+  )]
+  /// CHECK: Used for signing
+  pub queue_authority: AccountInfo<'info>,
+
+  #[account(
+        seeds = [b"token_inflow", fanout.key().as_ref(), mint.key().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        has_one = fanout,
+        has_one = mint,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+
+  #[account(mint::token_program = token_program)]
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+  pub token_program: Interface<'info, TokenInterface>,
+  pub cron_program: Program<'info, Cron>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RescheduleVoucherV0>) -> Result<()> {
+  let wallet = ctx.accounts.voucher.wallet;
+  let ixs = vec![Instruction {
+    program_id: crate::ID,
+    accounts: crate::accounts::ClaimV0 {
+      fanout: ctx.accounts.fanout.key(),
+      token_inflow: ctx.accounts.token_inflow.key(),
+      voucher: ctx.accounts.voucher.key(),
+      mint: ctx.accounts.mint.key(),
+      fanout_token_account: get_associated_token_address_with_program_id(
+        &ctx.accounts.fanout.key(),
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.token_program.key(),
+      ),
+      receiver_token_account: get_associated_token_address_with_program_id(
+        &wallet,
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.token_program.key(),
+      ),
+      token_program: ctx.accounts.token_program.key(),
+      wallet_share: ctx.accounts.voucher.wallet_share,
+      wallet,
+      cron_job: ctx.accounts.cron_job.key(),
+      cron_job_transaction: ctx.accounts.cron_job_transaction.key(),
+      cron_program: ctx.accounts.cron_program.key(),
+      queue_authority: ctx.accounts.queue_authority.key(),
+      system_program: ctx.accounts.system_program.key(),
+      ata_payer: Some(ata_payer_key(&ctx.accounts.fanout.key())),
+      associated_token_program: Some(associated_token::ID),
+      event_authority: Some(ctx.accounts.event_authority.key()),
+      program: Some(crate::ID),
+    }
+    .to_account_metas(None),
+    data: crate::instruction::ClaimV0.data(),
+  }];
+  let (compiled_tx, _) = compile_transaction(ixs, vec![])?;
+
+  add_cron_transaction_v0(
+    CpiContext::new_with_signer(
+      ctx.accounts.cron_program.to_account_info(),
+      AddCronTransactionV0 {
+        cron_job: ctx.accounts.cron_job.to_account_info(),
+        cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
+        payer: ctx.accounts.payer.to_account_info(),
+        authority: ctx.accounts.queue_authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+      },
+      &[queue_authority_seeds!(ctx.accounts.fanout)],
+    ),
+    AddCronTransactionArgsV0 {
+      index: ctx.accounts.voucher.cron_transaction_id,
+      transaction_source: tuktuk_program::cron::types::TransactionSourceV0::CompiledV0(
+        compiled_tx.into(),
+      ),
+    },
+  )?;
+
+  Ok(())
+}
@@ -0,0 +1,223 @@
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, InstructionData};
+use anchor_spl::{
+  associated_token::{self, get_associated_token_address_with_program_id},
+  token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use tuktuk_program::{
+  compile_transaction,
+  cron::{
+    accounts::CronJobV0,
+    cpi::{
+      accounts::{AddCronTransactionV0, RemoveCronTransactionV0},
+      add_cron_transaction_v0, remove_cron_transaction_v0,
+    },
+    program::Cron,
+    types::{AddCronTransactionArgsV0, RemoveCronTransactionArgsV0},
+  },
+};
+
+use super::{ata_payer_key, can_receive, claim_tokens};
+use crate::{
+  errors::ErrorCode,
+  events::{VoucherClaimWithheldV0, VoucherWalletRotatedV0},
+  queue_authority_seeds,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+/// Points a voucher at its wallet share's current wallet after paying the old wallet what it is
+/// owed. Anything the old wallet can't receive, e.g. because it is lost and its token account is
+/// closed, stays in `pending_amount` and is carried over to the new wallet along with any
+/// `escrowed_amount`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RotateVoucherWalletV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    constraint = cron_job.key() == token_inflow.voucher_cron_job(&fanout) @ ErrorCode::InvalidCronJob,
+  )]
+  pub cron_job: Box<Account<'info, CronJobV0>>,
+  #[account(
+    mut,
+    seeds = [b"cron_job_transaction", cron_job.key().as_ref(), &voucher.cron_transaction_id.to_le_bytes()[..]],
+    bump,
+    seeds::program = tuktuk_program::cron::ID,
+  )]
+  /// CHECK: Removed (if present) and re-added in CPI
+  pub cron_job_transaction: AccountInfo<'info>,
+  #[account(
+    seeds = [b"queue_authority"],
+    bump = fanout.queue_authority_bump,
+  )]
+  /// CHECK: Used for signing
+  pub queue_authority: AccountInfo<'info>,
+
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), mint.key().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        mut,
+        has_one = fanout,
+        has_one = mint,
+        has_one = wallet,
+        has_one = wallet_share,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+  /// CHECK: Loaded with WalletShareV0::load, which also reads wallet shares that aren't migrated
+  #[account(owner = crate::ID)]
+  pub wallet_share: AccountInfo<'info>,
+  /// CHECK: The wallet the voucher currently pays, checked by has_one
+  pub wallet: AccountInfo<'info>,
+
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fanout,
+        associated_token::token_program = token_program,
+    )]
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&wallet.key(), &mint.key(), &token_program.key()),
+    )]
+  /// CHECK: The old wallet's associated token account. If it can't receive the payout is escrowed
+  pub receiver_token_account: AccountInfo<'info>,
+
+  pub token_program: Interface<'info, TokenInterface>,
+  pub cron_program: Program<'info, Cron>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RotateVoucherWalletV0>) -> Result<()> {
+  let wallet_share = WalletShareV0::load(&ctx.accounts.wallet_share)?;
+
+  // Payouts are held while paused, so the old wallet couldn't be paid what it accrued before the
+  // wallet changes. Rescheduling without a wallet change is done with reschedule_voucher_v0
+  require!(
+    !ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout),
+    ErrorCode::FanoutPaused
+  );
+
+  // Settle everything owed up to now with the wallet the voucher currently pays. While suspended it
+  // is escrowed on the voucher instead, so a compromised wallet can be rotated out before it is paid
+  if wallet_share.suspended {
+    ctx.accounts.token_inflow.update_total_inflow(
+      ctx.accounts.fanout_token_account.amount,
+      &ctx.accounts.fanout,
+    );
+    let amount = ctx
+      .accounts
+      .voucher
+      .withhold_claim(&ctx.accounts.token_inflow);
+    emit_cpi!(VoucherClaimWithheldV0::new(&ctx.accounts.voucher, amount));
+  } else {
+    let event = claim_tokens(
+      &ctx.accounts.fanout,
+      &mut ctx.accounts.token_inflow,
+      &mut ctx.accounts.voucher,
+      &ctx.accounts.mint,
+      &mut ctx.accounts.fanout_token_account,
+      can_receive(
+        &ctx.accounts.receiver_token_account,
+        &ctx.accounts.token_program.to_account_info(),
+      )?
+      .then_some(&ctx.accounts.receiver_token_account),
+      &ctx.accounts.token_program.to_account_info(),
+    )?;
+    emit_cpi!(event);
+  }
+
+  let old_wallet = ctx.accounts.voucher.wallet;
+  let new_wallet = wallet_share.wallet;
+  ctx
+    .accounts
+    .voucher
+    .set_shares(wallet_share.shares, &ctx.accounts.token_inflow);
+  ctx.accounts.voucher.wallet = new_wallet;
+  emit_cpi!(VoucherWalletRotatedV0::new(
+    &ctx.accounts.voucher,
+    old_wallet
+  ));
+
+  let ixs = vec![Instruction {
+    program_id: crate::ID,
+    accounts: crate::accounts::ClaimV0 {
+      fanout: ctx.accounts.fanout.key(),
+      token_inflow: ctx.accounts.token_inflow.key(),
+      voucher: ctx.accounts.voucher.key(),
+      mint: ctx.accounts.mint.key(),
+      fanout_token_account: ctx.accounts.fanout_token_account.key(),
+      receiver_token_account: get_associated_token_address_with_program_id(
+        &new_wallet,
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.token_program.key(),
+      ),
+      token_program: ctx.accounts.token_program.key(),
+      wallet_share: ctx.accounts.wallet_share.key(),
+      wallet: new_wallet,
+      cron_job: ctx.accounts.cron_job.key(),
+      cron_job_transaction: ctx.accounts.cron_job_transaction.key(),
+      cron_program: ctx.accounts.cron_program.key(),
+      queue_authority: ctx.accounts.queue_authority.key(),
+      system_program: ctx.accounts.system_program.key(),
+      ata_payer: Some(ata_payer_key(&ctx.accounts.fanout.key())),
+      associated_token_program: Some(associated_token::ID),
+      event_authority: Some(ctx.accounts.event_authority.key()),
+      program: Some(crate::ID),
+    }
+    .to_account_metas(None),
+    data: crate::instruction::ClaimV0.data(),
+  }];
+  let (compiled_tx, _) = compile_transaction(ixs, vec![])?;
+
+  // Vouchers unscheduled for a cron job migration have no transaction to remove
+  if !ctx.accounts.cron_job_transaction.data_is_empty() {
+    remove_cron_transaction_v0(
+      CpiContext::new_with_signer(
+        ctx.accounts.cron_program.to_account_info(),
+        RemoveCronTransactionV0 {
+          cron_job: ctx.accounts.cron_job.to_account_info(),
+          cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
+          authority: ctx.accounts.queue_authority.to_account_info(),
+          system_program: ctx.accounts.system_program.to_account_info(),
+          rent_refund: ctx.accounts.payer.to_account_info(),
+        },
+        &[queue_authority_seeds!(ctx.accounts.fanout)],
+      ),
+      RemoveCronTransactionArgsV0 {
+        index: ctx.accounts.voucher.cron_transaction_id,
+      },
+    )?;
+  }
+
+  add_cron_transaction_v0(
+    CpiContext::new_with_signer(
+      ctx.accounts.cron_program.to_account_info(),
+      AddCronTransactionV0 {
+        cron_job: ctx.accounts.cron_job.to_account_info(),
+        cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
+        payer: ctx.accounts.payer.to_account_info(),
+        authority: ctx.accounts.queue_authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+      },
+      &[queue_authority_seeds!(ctx.accounts.fanout)],
+    ),
+    AddCronTransactionArgsV0 {
+      index: ctx.accounts.voucher.cron_transaction_id,
+      transaction_source: tuktuk_program::cron::types::TransactionSourceV0::CompiledV0(
+        compiled_tx.into(),
+      ),
+    },
+  )?;
+
+  Ok(())
+}
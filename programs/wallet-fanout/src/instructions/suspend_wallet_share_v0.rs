@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  errors::ErrorCode,
+  events::WalletShareSuspendedV0,
+  state::{FanoutV0, WalletShareV0},
+};
+
+/// Holds back the wallet share's payouts without changing its shares. Everything it is owed from now
+/// on, including what it hasn't claimed yet, accrues into its vouchers' `escrowed_amount`
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SuspendWalletShareV0<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    has_one = fanout,
+    constraint = !wallet_share.suspended @ ErrorCode::WalletShareSuspended,
+  )]
+  pub wallet_share: Account<'info, WalletShareV0>,
+}
+
+pub fn handler(ctx: Context<SuspendWalletShareV0>) -> Result<()> {
+  ctx.accounts.wallet_share.suspended = true;
+
+  emit_cpi!(WalletShareSuspendedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    wallet_share: ctx.accounts.wallet_share.key(),
+    wallet: ctx.accounts.wallet_share.wallet,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use tuktuk_program::cron::{
+  accounts::CronJobV0,
+  cpi::{accounts::RemoveCronTransactionV0, remove_cron_transaction_v0},
+  program::Cron,
+  types::RemoveCronTransactionArgsV0,
+};
+
+use super::cron_job_needs_migration;
+use crate::{
+  errors::ErrorCode,
+  queue_authority_seeds,
+  state::{FanoutV0, GlobalStateV0, TokenInflowV0, VoucherV0},
+};
+
+/// Removes a voucher's claim from a cron job that is being migrated, or from the fanout's once its
+/// inflow has its own cron job. Nothing owed is lost, the next claim after the voucher is rescheduled
+/// with reschedule_voucher_v0 pays out everything since its last claim.
+#[derive(Accounts)]
+pub struct UnscheduleVoucherV0<'info> {
+  #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+    )]
+  pub global_state: Box<Account<'info, GlobalStateV0>>,
+  #[account(
+        has_one = cron_job,
+    )]
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+        mut,
+        constraint = cron_job_needs_migration(&fanout, &cron_job, &global_state)
+          || token_inflow.voucher_cron_job(&fanout) != cron_job.key() @ ErrorCode::CronJobUpToDate,
+    )]
+  pub cron_job: Box<Account<'info, CronJobV0>>,
+  #[account(
+        mut,
+        seeds = [b"cron_job_transaction", cron_job.key().as_ref(), &voucher.cron_transaction_id.to_le_bytes()[..]],
+        bump,
+        seeds::program = tuktuk_program::cron::ID,
+    )]
+  /// CHECK: Removed in CPI
+  pub cron_job_transaction: AccountInfo<'info>,
+  #[account(
+        seeds = [b"queue_authority"],
+        bump = fanout.queue_authority_bump,
+    )]
+  /// CHECK: Used for signing
+  pub queue_authority: AccountInfo<'info>,
+  #[account(
+        has_one = fanout,
+        has_one = rent_refund,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+  #[account(
+        seeds = [b"token_inflow", fanout.key().as_ref(), voucher.mint.as_ref()],
+        bump = token_inflow.bump,
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+  /// CHECK: Receives the cron transaction rent, checked by has_one
+  #[account(mut)]
+  pub rent_refund: AccountInfo<'info>,
+  pub cron_program: Program<'info, Cron>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UnscheduleVoucherV0>) -> Result<()> {
+  remove_cron_transaction_v0(
+    CpiContext::new_with_signer(
+      ctx.accounts.cron_program.to_account_info(),
+      RemoveCronTransactionV0 {
+        cron_job: ctx.accounts.cron_job.to_account_info(),
+        cron_job_transaction: ctx.accounts.cron_job_transaction.to_account_info(),
+        authority: ctx.accounts.queue_authority.to_account_info(),
+        rent_refund: ctx.accounts.rent_refund.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+      },
+      &[queue_authority_seeds!(ctx.accounts.fanout)],
+    ),
+    RemoveCronTransactionArgsV0 {
+      index: ctx.accounts.voucher.cron_transaction_id,
+    },
+  )?;
+
+  Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  errors::ErrorCode,
+  events::WalletShareUnsuspendedV0,
+  settle::release_wallet_share,
+  state::{FanoutV0, WalletShareV0},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UnsuspendWalletShareV0Args {
+  /// Return the escrowed amounts to the pool instead of releasing them to the member
+  pub forfeit: bool,
+}
+
+/// Remaining accounts are the same as for update_wallet_share_v0
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UnsuspendWalletShareV0<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    has_one = fanout,
+    constraint = wallet_share.suspended @ ErrorCode::WalletShareNotSuspended,
+  )]
+  pub wallet_share: Account<'info, WalletShareV0>,
+}
+
+pub fn handler<'info>(
+  ctx: Context<'_, '_, 'info, 'info, UnsuspendWalletShareV0<'info>>,
+  args: UnsuspendWalletShareV0Args,
+) -> Result<()> {
+  let released = release_wallet_share(
+    &ctx.accounts.fanout,
+    &ctx.accounts.wallet_share.key(),
+    args.forfeit,
+    ctx.remaining_accounts,
+  )?;
+  ctx.accounts.wallet_share.suspended = false;
+
+  for event in released {
+    emit_cpi!(event);
+  }
+  emit_cpi!(WalletShareUnsuspendedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    wallet_share: ctx.accounts.wallet_share.key(),
+    wallet: ctx.accounts.wallet_share.wallet,
+    forfeited: args.forfeit,
+  });
+
+  Ok(())
+}
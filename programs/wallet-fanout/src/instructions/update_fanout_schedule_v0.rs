@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::FanoutScheduleUpdatedV0, resize_to_fit::resize_to_fit, state::FanoutV0};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateFanoutScheduleV0Args {
+  pub schedule: String,
+}
+
+/// Cron jobs can't be edited in place, so this only records the new schedule. Vouchers are then
+/// moved with unschedule_voucher_v0, migrate_fanout_cron_job_v0 and reschedule_voucher_v0, keeping
+/// their `cron_transaction_id`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateFanoutScheduleV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+  ctx: Context<UpdateFanoutScheduleV0>,
+  args: UpdateFanoutScheduleV0Args,
+) -> Result<()> {
+  ctx.accounts.fanout.pending_schedule = Some(args.schedule.clone());
+
+  resize_to_fit(
+    &ctx.accounts.payer.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.fanout,
+  )?;
+
+  emit_cpi!(FanoutScheduleUpdatedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    pending_schedule: args.schedule,
+  });
+
+  Ok(())
+}
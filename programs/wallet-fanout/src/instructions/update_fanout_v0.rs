@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  events::FanoutUpdatedV0,
+  resize_to_fit::resize_to_fit,
+  state::{FanoutV0, TransferFeePolicy},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateFanoutV0Args {
+  pub transfer_fee_policy: Option<TransferFeePolicy>,
+  /// Seconds before undelivered pending amounts can be forfeited. `Some(0)` removes the window
+  pub claim_window_secs: Option<u64>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateFanoutV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpdateFanoutV0>, args: UpdateFanoutV0Args) -> Result<()> {
+  let fanout = &mut ctx.accounts.fanout;
+
+  if let Some(transfer_fee_policy) = args.transfer_fee_policy {
+    fanout.transfer_fee_policy = transfer_fee_policy;
+  }
+  if let Some(claim_window_secs) = args.claim_window_secs {
+    fanout.claim_window_secs = (claim_window_secs > 0).then_some(claim_window_secs);
+  }
+
+  // Fanouts created before new fields were added may need more space
+  resize_to_fit(
+    &ctx.accounts.payer.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.fanout,
+  )?;
+
+  emit_cpi!(FanoutUpdatedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    transfer_fee_policy: ctx.accounts.fanout.transfer_fee_policy,
+    claim_window_secs: ctx.accounts.fanout.claim_window_secs,
+  });
+
+  Ok(())
+}
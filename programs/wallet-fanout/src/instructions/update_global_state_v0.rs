@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use tuktuk_program::{TaskQueueAuthorityV0, TaskQueueV0};
+
+use crate::{errors::ErrorCode, events::GlobalStateUpdatedV0, state::GlobalStateV0};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateGlobalStateV0Args {
+  /// Proposes a new authority, which takes over once it calls accept_global_state_authority_v0, or
+  /// None to cancel a pending proposal
+  pub pending_authority: Option<Pubkey>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateGlobalStateV0<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+    )]
+  pub global_state: Box<Account<'info, GlobalStateV0>>,
+
+  #[account(
+        seeds = [b"queue_authority"],
+        bump
+    )]
+  /// CHECK: This is a PDA that is the authority on the task queue
+  pub queue_authority: UncheckedAccount<'info>,
+
+  /// New task queue for fanouts. Existing fanouts are moved over with migrate_fanout_cron_job_v0
+  pub task_queue: Option<Box<Account<'info, TaskQueueV0>>>,
+  pub task_queue_authority: Option<Box<Account<'info, TaskQueueAuthorityV0>>>,
+}
+
+pub fn handler(ctx: Context<UpdateGlobalStateV0>, args: UpdateGlobalStateV0Args) -> Result<()> {
+  ctx.accounts.global_state.pending_authority = args.pending_authority;
+
+  if let Some(task_queue) = &ctx.accounts.task_queue {
+    let task_queue_authority = ctx
+      .accounts
+      .task_queue_authority
+      .as_ref()
+      .ok_or(error!(ErrorCode::InvalidTaskQueueAuthority))?;
+    let expected_task_queue_authority = Pubkey::find_program_address(
+      &[
+        b"task_queue_authority",
+        task_queue.key().as_ref(),
+        ctx.accounts.queue_authority.key().as_ref(),
+      ],
+      &tuktuk_program::tuktuk::ID,
+    )
+    .0;
+    require_keys_eq!(
+      task_queue_authority.key(),
+      expected_task_queue_authority,
+      ErrorCode::InvalidTaskQueueAuthority
+    );
+    ctx.accounts.global_state.task_queue = task_queue.key();
+  }
+
+  emit_cpi!(GlobalStateUpdatedV0 {
+    global_state: ctx.accounts.global_state.key(),
+    pending_authority: ctx.accounts.global_state.pending_authority,
+    task_queue: ctx.accounts.global_state.task_queue,
+  });
+
+  Ok(())
+}
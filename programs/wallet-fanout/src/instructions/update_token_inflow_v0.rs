@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  events::TokenInflowUpdatedV0,
+  state::{FanoutV0, TokenInflowV0},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateTokenInflowV0Args {
+  pub min_payout: Option<u64>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTokenInflowV0<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  #[account(
+    mut,
+    has_one = fanout,
+  )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+}
+
+pub fn handler(ctx: Context<UpdateTokenInflowV0>, args: UpdateTokenInflowV0Args) -> Result<()> {
+  let token_inflow = &mut ctx.accounts.token_inflow;
+
+  if let Some(min_payout) = args.min_payout {
+    token_inflow.min_payout = min_payout;
+  }
+
+  emit_cpi!(TokenInflowUpdatedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    token_inflow: ctx.accounts.token_inflow.key(),
+    mint: ctx.accounts.token_inflow.mint,
+    min_payout: ctx.accounts.token_inflow.min_payout,
+  });
+
+  Ok(())
+}
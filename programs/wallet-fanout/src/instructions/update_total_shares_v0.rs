@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  errors::ErrorCode, events::TotalSharesUpdatedV0, settle::settle_token_inflows, state::FanoutV0,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateTotalSharesV0Args {
+  pub total_shares: u32,
+}
+
+/// Remaining accounts are a `[token_inflow, fanout_token_account]` pair for every token inflow of the
+/// fanout, with the fanout itself in place of the token account for the native inflow
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTotalSharesV0<'info> {
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+}
+
+pub fn handler<'info>(
+  ctx: Context<'_, '_, 'info, 'info, UpdateTotalSharesV0<'info>>,
+  args: UpdateTotalSharesV0Args,
+) -> Result<()> {
+  require_gte!(
+    args.total_shares,
+    ctx.accounts.fanout.total_shares_issued,
+    ErrorCode::TotalSharesExceeded
+  );
+
+  // Everything that arrived so far is split under the old total shares
+  settle_token_inflows(&ctx.accounts.fanout, ctx.remaining_accounts)?;
+
+  let old_total_shares = ctx.accounts.fanout.total_shares;
+  ctx.accounts.fanout.total_shares = args.total_shares;
+
+  emit_cpi!(TotalSharesUpdatedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    old_total_shares,
+    new_total_shares: args.total_shares,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  events::UnissuedSharePolicyUpdatedV0,
+  resize_to_fit::resize_to_fit,
+  settle::settle_token_inflows,
+  state::{FanoutV0, UnissuedSharePolicy},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateUnissuedSharePolicyV0Args {
+  pub unissued_share_policy: UnissuedSharePolicy,
+}
+
+/// Remaining accounts are a `[token_inflow, fanout_token_account]` pair for every token inflow of the
+/// fanout, with the fanout itself in place of the token account for the native inflow
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateUnissuedSharePolicyV0<'info> {
+  #[account(mut)]
+  pub payer: Signer<'info>,
+  pub authority: Signer<'info>,
+  #[account(
+    mut,
+    has_one = authority
+  )]
+  pub fanout: Account<'info, FanoutV0>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+  ctx: Context<'_, '_, 'info, 'info, UpdateUnissuedSharePolicyV0<'info>>,
+  args: UpdateUnissuedSharePolicyV0Args,
+) -> Result<()> {
+  // Everything that arrived so far is split under the old policy
+  settle_token_inflows(&ctx.accounts.fanout, ctx.remaining_accounts)?;
+
+  let old_unissued_share_policy = ctx.accounts.fanout.unissued_share_policy;
+  ctx.accounts.fanout.unissued_share_policy = args.unissued_share_policy;
+
+  // Fanouts created before new fields were added may need more space
+  resize_to_fit(
+    &ctx.accounts.payer.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.fanout,
+  )?;
+
+  emit_cpi!(UnissuedSharePolicyUpdatedV0 {
+    fanout: ctx.accounts.fanout.key(),
+    old_unissued_share_policy,
+    new_unissued_share_policy: args.unissued_share_policy,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::VoucherUpdatedV0, state::VoucherV0};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateVoucherV0Args {
+  /// Only takes effect above the inflow's `min_payout`
+  pub min_payout: Option<u64>,
+}
+
+/// Lets the voucher's wallet adjust how it is paid out
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateVoucherV0<'info> {
+  pub wallet: Signer<'info>,
+  #[account(
+    mut,
+    has_one = wallet,
+  )]
+  pub voucher: Account<'info, VoucherV0>,
+}
+
+pub fn handler(ctx: Context<UpdateVoucherV0>, args: UpdateVoucherV0Args) -> Result<()> {
+  let voucher = &mut ctx.accounts.voucher;
+
+  if let Some(min_payout) = args.min_payout {
+    voucher.min_payout = min_payout;
+  }
+
+  emit_cpi!(VoucherUpdatedV0 {
+    fanout: ctx.accounts.voucher.fanout,
+    voucher: ctx.accounts.voucher.key(),
+    wallet: ctx.accounts.wallet.key(),
+    mint: ctx.accounts.voucher.mint,
+    min_payout: ctx.accounts.voucher.min_payout,
+  });
+
+  Ok(())
+}
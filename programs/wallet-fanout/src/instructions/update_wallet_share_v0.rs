@@ -2,7 +2,12 @@ use std::cmp::max;
 
 use anchor_lang::prelude::*;
 
-use crate::state::{FanoutV0, WalletShareV0};
+use crate::{
+  events::{emit_optional_cpi, WalletShareUpdatedV0},
+  program::WalletFanout,
+  settle::settle_wallet_share,
+  state::{FanoutV0, WalletShareV0},
+};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct UpdateWalletShareV0Args {
@@ -10,6 +15,9 @@ pub struct UpdateWalletShareV0Args {
   pub index: u32,
 }
 
+/// Remaining accounts are a `[token_inflow, fanout_token_account, voucher]` triple for every token
+/// inflow of the fanout, with the fanout itself in place of the token account for the native inflow.
+/// The voucher is this wallet share's voucher for the inflow's mint, which may not exist.
 #[derive(Accounts)]
 #[instruction(args: UpdateWalletShareV0Args)]
 pub struct UpdateWalletShareV0<'info> {
@@ -28,18 +36,38 @@ pub struct UpdateWalletShareV0<'info> {
   #[account(
         init_if_needed,
         payer = payer,
-        space = 8 + std::mem::size_of::<WalletShareV0>(),
+        space = 8 + 60 + std::mem::size_of::<WalletShareV0>(),
         seeds = [b"wallet_share", fanout.key().as_ref(), &args.index.to_le_bytes()],
         bump
     )]
   pub wallet_share: Account<'info, WalletShareV0>,
 
   pub system_program: Program<'info, System>,
+  // Optional so that clients built before events existed keep working
+  /// CHECK: Signs the event self-CPI
+  #[account(
+    seeds = [b"__event_authority"],
+    bump,
+  )]
+  pub event_authority: Option<AccountInfo<'info>>,
+  pub program: Option<Program<'info, WalletFanout>>,
 }
 
-pub fn handler(ctx: Context<UpdateWalletShareV0>, args: UpdateWalletShareV0Args) -> Result<()> {
+pub fn handler<'info>(
+  ctx: Context<'_, '_, 'info, 'info, UpdateWalletShareV0<'info>>,
+  args: UpdateWalletShareV0Args,
+) -> Result<()> {
+  // Everything that arrived so far is owed at the old share counts
+  settle_wallet_share(
+    &ctx.accounts.fanout,
+    &ctx.accounts.wallet_share,
+    args.shares,
+    ctx.remaining_accounts,
+  )?;
+
   let fanout = &mut ctx.accounts.fanout;
   let wallet_share = &mut ctx.accounts.wallet_share;
+  let old_shares = wallet_share.shares;
 
   fanout.total_shares_issued = fanout
     .total_shares_issued
@@ -62,5 +90,18 @@ pub fn handler(ctx: Context<UpdateWalletShareV0>, args: UpdateWalletShareV0Args)
 
   fanout.next_share_id = max(args.index + 1, fanout.next_share_id);
 
+  emit_optional_cpi(
+    ctx.accounts.event_authority.as_ref(),
+    ctx.bumps.event_authority,
+    WalletShareUpdatedV0 {
+      fanout: fanout.key(),
+      wallet_share: wallet_share.key(),
+      wallet: wallet_share.wallet,
+      old_shares,
+      new_shares: args.shares,
+      total_shares_issued: fanout.total_shares_issued,
+    },
+  )?;
+
   Ok(())
 }
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use super::{distributable_lamports, pay_lamports};
+use crate::{
+  errors::ErrorCode,
+  events::PendingWithdrawnV0,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawPendingSolV0<'info> {
+  pub wallet: Signer<'info>,
+  #[account(mut)]
+  pub fanout: Account<'info, FanoutV0>,
+
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), Pubkey::default().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        mut,
+        has_one = fanout,
+        has_one = wallet,
+        constraint = voucher.mint == token_inflow.mint,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+
+  /// CHECK: Any account the wallet wants the lamports sent to
+  #[account(mut)]
+  pub destination: AccountInfo<'info>,
+  /// CHECK: Checked against the voucher, may already be closed
+  #[account(address = voucher.wallet_share)]
+  pub wallet_share: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<WithdrawPendingSolV0>) -> Result<()> {
+  require!(
+    !ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout),
+    ErrorCode::FanoutPaused
+  );
+  require!(
+    !WalletShareV0::is_suspended(&ctx.accounts.wallet_share)?,
+    ErrorCode::WalletShareSuspended
+  );
+
+  let amount = ctx.accounts.voucher.pending_amount;
+  require_gt!(amount, 0, ErrorCode::NoPendingAmount);
+
+  // Record any inflow since the last snapshot before the balance drops
+  let balance = distributable_lamports(&ctx.accounts.fanout.to_account_info())?;
+  ctx
+    .accounts
+    .token_inflow
+    .update_total_inflow(balance, &ctx.accounts.fanout);
+
+  pay_lamports(
+    &ctx.accounts.fanout,
+    &mut ctx.accounts.token_inflow,
+    &mut ctx.accounts.voucher,
+    &ctx.accounts.destination,
+    amount,
+  )?;
+  ctx.accounts.voucher.pending_amount = 0;
+
+  emit_cpi!(PendingWithdrawnV0 {
+    fanout: ctx.accounts.fanout.key(),
+    voucher: ctx.accounts.voucher.key(),
+    wallet: ctx.accounts.wallet.key(),
+    mint: Pubkey::default(),
+    destination: ctx.accounts.destination.key(),
+    amount,
+  });
+
+  Ok(())
+}
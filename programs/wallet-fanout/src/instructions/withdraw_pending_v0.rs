@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::transfer_from_fanout;
+use crate::{
+  errors::ErrorCode,
+  events::PendingWithdrawnV0,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawPendingV0<'info> {
+  pub wallet: Signer<'info>,
+  pub fanout: Account<'info, FanoutV0>,
+
+  #[account(
+        mut,
+        seeds = [b"token_inflow", fanout.key().as_ref(), mint.key().as_ref()],
+        bump = token_inflow.bump
+    )]
+  pub token_inflow: Account<'info, TokenInflowV0>,
+
+  #[account(
+        mut,
+        has_one = fanout,
+        has_one = mint,
+        has_one = wallet,
+    )]
+  pub voucher: Account<'info, VoucherV0>,
+
+  pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fanout,
+        associated_token::token_program = token_program,
+    )]
+  pub fanout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+  pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  pub token_program: Interface<'info, TokenInterface>,
+  /// CHECK: Checked against the voucher, may already be closed
+  #[account(address = voucher.wallet_share)]
+  pub wallet_share: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<WithdrawPendingV0>) -> Result<()> {
+  require!(
+    !ctx.accounts.token_inflow.is_paused(&ctx.accounts.fanout),
+    ErrorCode::FanoutPaused
+  );
+  require!(
+    !WalletShareV0::is_suspended(&ctx.accounts.wallet_share)?,
+    ErrorCode::WalletShareSuspended
+  );
+
+  let amount = ctx.accounts.voucher.pending_amount;
+  require_gt!(amount, 0, ErrorCode::NoPendingAmount);
+
+  // Record any inflow since the last snapshot before the balance drops
+  ctx.accounts.token_inflow.update_total_inflow(
+    ctx.accounts.fanout_token_account.amount,
+    &ctx.accounts.fanout,
+  );
+
+  transfer_from_fanout(
+    &ctx.accounts.fanout,
+    &mut ctx.accounts.token_inflow,
+    &mut ctx.accounts.voucher,
+    &ctx.accounts.mint,
+    &mut ctx.accounts.fanout_token_account,
+    &ctx.accounts.destination.to_account_info(),
+    &ctx.accounts.token_program.to_account_info(),
+    amount,
+  )?;
+  ctx.accounts.voucher.pending_amount = 0;
+
+  emit_cpi!(PendingWithdrawnV0 {
+    fanout: ctx.accounts.fanout.key(),
+    voucher: ctx.accounts.voucher.key(),
+    wallet: ctx.accounts.wallet.key(),
+    mint: ctx.accounts.mint.key(),
+    destination: ctx.accounts.destination.key(),
+    amount,
+  });
+
+  Ok(())
+}
@@ -5,8 +5,10 @@ use {default_env::default_env, solana_security_txt::security_txt};
 declare_id!("fanCjX4iwbn41FSuZSvoSdro2XjUKuWyZFbegnqtmTX");
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod resize_to_fit;
+pub mod settle;
 pub mod state;
 
 pub use instructions::*;
@@ -35,6 +37,19 @@ pub mod wallet_fanout {
     instructions::initialize_global_state_v0::handler(ctx)
   }
 
+  pub fn update_global_state_v0(
+    ctx: Context<UpdateGlobalStateV0>,
+    args: UpdateGlobalStateV0Args,
+  ) -> Result<()> {
+    instructions::update_global_state_v0::handler(ctx, args)
+  }
+
+  pub fn accept_global_state_authority_v0(
+    ctx: Context<AcceptGlobalStateAuthorityV0>,
+  ) -> Result<()> {
+    instructions::accept_global_state_authority_v0::handler(ctx)
+  }
+
   pub fn initialize_fanout_v0(
     ctx: Context<InitializeFanoutV0>,
     args: InitializeFanoutV0Args,
@@ -42,26 +57,164 @@ pub mod wallet_fanout {
     instructions::initialize_fanout_v0::handler(ctx, args)
   }
 
-  pub fn update_wallet_share_v0(
-    ctx: Context<UpdateWalletShareV0>,
+  pub fn update_fanout_v0(ctx: Context<UpdateFanoutV0>, args: UpdateFanoutV0Args) -> Result<()> {
+    instructions::update_fanout_v0::handler(ctx, args)
+  }
+
+  pub fn update_fanout_schedule_v0(
+    ctx: Context<UpdateFanoutScheduleV0>,
+    args: UpdateFanoutScheduleV0Args,
+  ) -> Result<()> {
+    instructions::update_fanout_schedule_v0::handler(ctx, args)
+  }
+
+  pub fn update_unissued_share_policy_v0<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateUnissuedSharePolicyV0<'info>>,
+    args: UpdateUnissuedSharePolicyV0Args,
+  ) -> Result<()> {
+    instructions::update_unissued_share_policy_v0::handler(ctx, args)
+  }
+
+  pub fn pause_fanout_v0(ctx: Context<PauseFanoutV0>) -> Result<()> {
+    instructions::pause_fanout_v0::handler(ctx)
+  }
+
+  pub fn resume_fanout_v0(ctx: Context<ResumeFanoutV0>) -> Result<()> {
+    instructions::resume_fanout_v0::handler(ctx)
+  }
+
+  pub fn propose_fanout_authority_v0(
+    ctx: Context<ProposeFanoutAuthorityV0>,
+    args: ProposeFanoutAuthorityV0Args,
+  ) -> Result<()> {
+    instructions::propose_fanout_authority_v0::handler(ctx, args)
+  }
+
+  pub fn accept_fanout_authority_v0(ctx: Context<AcceptFanoutAuthorityV0>) -> Result<()> {
+    instructions::accept_fanout_authority_v0::handler(ctx)
+  }
+
+  pub fn update_total_shares_v0<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateTotalSharesV0<'info>>,
+    args: UpdateTotalSharesV0Args,
+  ) -> Result<()> {
+    instructions::update_total_shares_v0::handler(ctx, args)
+  }
+
+  pub fn update_wallet_share_v0<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateWalletShareV0<'info>>,
     args: UpdateWalletShareV0Args,
   ) -> Result<()> {
     instructions::update_wallet_share_v0::handler(ctx, args)
   }
 
+  pub fn suspend_wallet_share_v0(ctx: Context<SuspendWalletShareV0>) -> Result<()> {
+    instructions::suspend_wallet_share_v0::handler(ctx)
+  }
+
+  pub fn unsuspend_wallet_share_v0<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnsuspendWalletShareV0<'info>>,
+    args: UnsuspendWalletShareV0Args,
+  ) -> Result<()> {
+    instructions::unsuspend_wallet_share_v0::handler(ctx, args)
+  }
+
   pub fn initialize_voucher_v0(ctx: Context<InitializeVoucherV0>) -> Result<()> {
     instructions::initialize_voucher_v0::handler(ctx)
   }
 
+  pub fn update_voucher_v0(ctx: Context<UpdateVoucherV0>, args: UpdateVoucherV0Args) -> Result<()> {
+    instructions::update_voucher_v0::handler(ctx, args)
+  }
+
   pub fn claim_v0(ctx: Context<ClaimV0>) -> Result<()> {
     instructions::claim_v0::handler(ctx)
   }
 
+  pub fn preview_claim_v0(ctx: Context<PreviewClaimV0>) -> Result<ClaimPreviewV0> {
+    instructions::preview_claim_v0::handler(ctx)
+  }
+
+  pub fn claim_to_v0(ctx: Context<ClaimToV0>) -> Result<()> {
+    instructions::claim_to_v0::handler(ctx)
+  }
+
+  pub fn initialize_sol_voucher_v0(ctx: Context<InitializeSolVoucherV0>) -> Result<()> {
+    instructions::initialize_sol_voucher_v0::handler(ctx)
+  }
+
+  pub fn claim_sol_v0(ctx: Context<ClaimSolV0>) -> Result<()> {
+    instructions::claim_sol_v0::handler(ctx)
+  }
+
+  pub fn close_sol_voucher_v0(ctx: Context<CloseSolVoucherV0>) -> Result<()> {
+    instructions::close_sol_voucher_v0::handler(ctx)
+  }
+
+  pub fn close_sol_inflow_v0(ctx: Context<CloseSolInflowV0>) -> Result<()> {
+    instructions::close_sol_inflow_v0::handler(ctx)
+  }
+
+  pub fn withdraw_pending_v0(ctx: Context<WithdrawPendingV0>) -> Result<()> {
+    instructions::withdraw_pending_v0::handler(ctx)
+  }
+
+  pub fn withdraw_pending_sol_v0(ctx: Context<WithdrawPendingSolV0>) -> Result<()> {
+    instructions::withdraw_pending_sol_v0::handler(ctx)
+  }
+
+  pub fn claim_treasury_v0(ctx: Context<ClaimTreasuryV0>) -> Result<()> {
+    instructions::claim_treasury_v0::handler(ctx)
+  }
+
+  pub fn claim_treasury_sol_v0(ctx: Context<ClaimTreasurySolV0>) -> Result<()> {
+    instructions::claim_treasury_sol_v0::handler(ctx)
+  }
+
+  pub fn forfeit_voucher_v0(
+    ctx: Context<ForfeitVoucherV0>,
+    args: ForfeitVoucherV0Args,
+  ) -> Result<()> {
+    instructions::forfeit_voucher_v0::handler(ctx, args)
+  }
+
+  pub fn rotate_voucher_wallet_v0(ctx: Context<RotateVoucherWalletV0>) -> Result<()> {
+    instructions::rotate_voucher_wallet_v0::handler(ctx)
+  }
+
+  pub fn rotate_sol_voucher_wallet_v0(ctx: Context<RotateSolVoucherWalletV0>) -> Result<()> {
+    instructions::rotate_sol_voucher_wallet_v0::handler(ctx)
+  }
+
+  pub fn reschedule_voucher_v0(ctx: Context<RescheduleVoucherV0>) -> Result<()> {
+    instructions::reschedule_voucher_v0::handler(ctx)
+  }
+
+  pub fn reschedule_sol_voucher_v0(ctx: Context<RescheduleSolVoucherV0>) -> Result<()> {
+    instructions::reschedule_sol_voucher_v0::handler(ctx)
+  }
+
+  pub fn update_token_inflow_v0(
+    ctx: Context<UpdateTokenInflowV0>,
+    args: UpdateTokenInflowV0Args,
+  ) -> Result<()> {
+    instructions::update_token_inflow_v0::handler(ctx, args)
+  }
+
+  pub fn initialize_token_inflow_cron_job_v0(
+    ctx: Context<InitializeTokenInflowCronJobV0>,
+    args: InitializeTokenInflowCronJobV0Args,
+  ) -> Result<()> {
+    instructions::initialize_token_inflow_cron_job_v0::handler(ctx, args)
+  }
+
   pub fn close_token_inflow_v0(ctx: Context<CloseTokenInflowV0>) -> Result<()> {
     instructions::close_token_inflow_v0::handler(ctx)
   }
 
-  pub fn close_wallet_share_v0(ctx: Context<CloseWalletShareV0>) -> Result<()> {
+  pub fn close_wallet_share_v0<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseWalletShareV0<'info>>,
+  ) -> Result<()> {
     instructions::close_wallet_share_v0::handler(ctx)
   }
 
@@ -73,6 +226,28 @@ pub mod wallet_fanout {
     instructions::close_fanout_v0::handler(ctx)
   }
 
+  pub fn unschedule_voucher_v0(ctx: Context<UnscheduleVoucherV0>) -> Result<()> {
+    instructions::unschedule_voucher_v0::handler(ctx)
+  }
+
+  pub fn migrate_fanout_cron_job_v0(ctx: Context<MigrateFanoutCronJobV0>) -> Result<()> {
+    instructions::migrate_fanout_cron_job_v0::handler(ctx)
+  }
+
+  pub fn migrate_accumulator_v0<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateAccumulatorV0<'info>>,
+  ) -> Result<()> {
+    instructions::migrate_accumulator_v0::handler(ctx)
+  }
+
+  pub fn migrate_voucher_v0(ctx: Context<MigrateVoucherV0>) -> Result<()> {
+    instructions::migrate_voucher_v0::handler(ctx)
+  }
+
+  pub fn migrate_wallet_share_v0(ctx: Context<MigrateWalletShareV0>) -> Result<()> {
+    instructions::migrate_wallet_share_v0::handler(ctx)
+  }
+
   pub fn requeue_fanout_task_v0(
     ctx: Context<RequeueFanoutTaskV0>,
     args: RequeueFanoutTaskArgsV0,
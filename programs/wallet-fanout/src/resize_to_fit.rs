@@ -22,24 +22,41 @@ impl Write for IgnoreWriter {
   }
 }
 
-/// Resizes the account to the size of the struct
+/// The size `resize_to_fit` grows or shrinks `account` to
+pub fn fitted_size<T: AccountSerialize>(account: &T) -> Result<usize> {
+  let writer = &mut IgnoreWriter { total: 0 };
+  account.try_serialize(writer)?;
+  Ok(writer.total + 64) // Pad enough for two pubkeys so deserialize doesn't fail
+}
+
+/// Resizes the account to the size of the struct. The payer funds the extra rent when it grows and
+/// gets the freed rent back when it shrinks
 pub fn resize_to_fit<'info, T: AccountSerialize + AccountDeserialize + Owner + Clone>(
   payer: &AccountInfo<'info>,
   system_program: &AccountInfo<'info>,
   account: &Account<'info, T>,
 ) -> Result<()> {
   let rent = Rent::get()?;
-  let writer = &mut IgnoreWriter { total: 0 };
-  account.try_serialize(writer)?;
-  let new_size = writer.total + 64; // Pad enough for two pubkeys so deserialize doesn't fail
+  let new_size = fitted_size(&**account)?;
   let new_minimum_balance = rent.minimum_balance(new_size);
-  let lamports_diff = new_minimum_balance.saturating_sub(account.to_account_info().lamports());
   let old_size = account.to_account_info().data.borrow().len();
+  // Only move the change in rent, so lamports held above the minimum (e.g. undistributed SOL on a
+  // fanout) are never consumed as rent or refunded
+  let old_minimum_balance = rent
+    .minimum_balance(old_size)
+    .min(account.to_account_info().lamports());
+  let lamports_diff = new_minimum_balance.saturating_sub(old_minimum_balance);
+  let refund = old_minimum_balance.saturating_sub(new_minimum_balance);
 
   if new_size > old_size && (new_size - old_size) > MAX_PERMITTED_DATA_INCREASE {
     return Err(error!(ErrorCode::InvalidDataIncrease));
   }
-  msg!("Resizing to {} with lamports {}", new_size, lamports_diff);
+  msg!(
+    "Resizing to {} with lamports {}, refunding {}",
+    new_size,
+    lamports_diff,
+    refund
+  );
   invoke(
     &system_instruction::transfer(payer.key, &account.key(), lamports_diff),
     &[
@@ -48,6 +65,11 @@ pub fn resize_to_fit<'info, T: AccountSerialize + AccountDeserialize + Owner + C
       system_program.clone(),
     ],
   )?;
+  if refund > 0 {
+    // The account is owned by this program, so lamports can be moved directly
+    account.to_account_info().sub_lamports(refund)?;
+    payer.add_lamports(refund)?;
+  }
 
   account.to_account_info().realloc(new_size, false)?;
 
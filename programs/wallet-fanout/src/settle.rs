@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  associated_token::get_associated_token_address_with_program_id, token_interface::TokenAccount,
+};
+
+use crate::{
+  errors::ErrorCode,
+  events::EscrowReleasedV0,
+  instructions::distributable_lamports,
+  state::{FanoutV0, TokenInflowV0, VoucherV0, WalletShareV0},
+};
+
+/// The balance tracked by `token_inflow`. `balance_account` is the fanout's token account, or the
+/// fanout itself for the native inflow
+pub fn inflow_balance(
+  fanout: &AccountInfo,
+  token_inflow: &TokenInflowV0,
+  balance_account: &AccountInfo,
+) -> Result<u64> {
+  if token_inflow.is_native() {
+    require_keys_eq!(
+      balance_account.key(),
+      fanout.key(),
+      ErrorCode::InvalidInflowAccounts
+    );
+    return distributable_lamports(balance_account);
+  }
+
+  require_keys_eq!(
+    balance_account.key(),
+    get_associated_token_address_with_program_id(
+      &fanout.key(),
+      &token_inflow.mint,
+      balance_account.owner
+    ),
+    ErrorCode::InvalidInflowAccounts
+  );
+  require!(
+    *balance_account.owner == anchor_spl::token::ID
+      || *balance_account.owner == anchor_spl::token_2022::ID,
+    ErrorCode::InvalidInflowAccounts
+  );
+  Ok(TokenAccount::try_deserialize(&mut &balance_account.try_borrow_data()?[..])?.amount)
+}
+
+/// Brings one of the fanout's token inflows up to date with its balance, checking it hasn't already
+/// been settled in this instruction
+fn settle_token_inflow<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  token_inflow: &'info AccountInfo<'info>,
+  balance_account: &'info AccountInfo<'info>,
+  settled: &mut Vec<Pubkey>,
+) -> Result<Account<'info, TokenInflowV0>> {
+  let mut token_inflow = Account::<TokenInflowV0>::try_from(token_inflow)?;
+  require_keys_eq!(
+    token_inflow.fanout,
+    fanout.key(),
+    ErrorCode::InvalidInflowAccounts
+  );
+  require!(
+    !settled.contains(&token_inflow.key()),
+    ErrorCode::InvalidInflowAccounts
+  );
+  settled.push(token_inflow.key());
+
+  let balance = inflow_balance(&fanout.to_account_info(), &token_inflow, balance_account)?;
+  token_inflow.update_total_inflow(balance, fanout);
+
+  Ok(token_inflow)
+}
+
+/// Credits everything that has arrived in each of the fanout's token inflows under the current share
+/// configuration. Must run before anything that changes how inflow is split. `accounts` holds a
+/// `[token_inflow, balance_account]` pair for every inflow of the fanout.
+pub fn settle_token_inflows<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+  require_eq!(
+    accounts.len(),
+    fanout.num_inflows as usize * 2,
+    ErrorCode::InvalidInflowAccounts
+  );
+
+  let mut settled = Vec::with_capacity(fanout.num_inflows as usize);
+  for pair in accounts.chunks(2) {
+    let token_inflow = settle_token_inflow(fanout, &pair[0], &pair[1], &mut settled)?;
+    token_inflow.exit(&crate::ID)?;
+  }
+
+  Ok(())
+}
+
+/// The voucher of `wallet_share` for `token_inflow`'s mint, if it exists. `voucher` must be its
+/// address
+fn wallet_share_voucher<'info>(
+  fanout: &Pubkey,
+  token_inflow: &TokenInflowV0,
+  wallet_share: &Pubkey,
+  voucher: &'info AccountInfo<'info>,
+) -> Result<Option<Account<'info, VoucherV0>>> {
+  let voucher_key = Pubkey::find_program_address(
+    &[
+      b"voucher",
+      fanout.as_ref(),
+      token_inflow.mint.as_ref(),
+      wallet_share.as_ref(),
+    ],
+    &crate::ID,
+  )
+  .0;
+  require_keys_eq!(voucher.key(), voucher_key, ErrorCode::InvalidInflowAccounts);
+  if voucher.data_is_empty() {
+    return Ok(None);
+  }
+  Ok(Some(Account::<VoucherV0>::try_from(voucher)?))
+}
+
+/// Settles every token inflow, then moves what each of `wallet_share`'s vouchers is owed at its
+/// current share count into its `pending_amount`, or `escrowed_amount` while suspended, and switches
+/// it to `new_shares`. `accounts` holds a `[token_inflow, balance_account, voucher]` triple for every
+/// inflow of the fanout, where the voucher account may not exist.
+pub fn settle_wallet_share<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  wallet_share: &Account<'info, WalletShareV0>,
+  new_shares: u32,
+  accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+  require_eq!(
+    accounts.len(),
+    fanout.num_inflows as usize * 3,
+    ErrorCode::InvalidInflowAccounts
+  );
+
+  let unix_ts = Clock::get()?.unix_timestamp;
+  let mut settled = Vec::with_capacity(fanout.num_inflows as usize);
+  for triple in accounts.chunks(3) {
+    let token_inflow = settle_token_inflow(fanout, &triple[0], &triple[1], &mut settled)?;
+    if let Some(mut voucher) = wallet_share_voucher(
+      &fanout.key(),
+      &token_inflow,
+      &wallet_share.key(),
+      &triple[2],
+    )? {
+      voucher.hold_claim(&token_inflow, wallet_share.suspended, unix_ts);
+      voucher.set_shares(new_shares, &token_inflow);
+      voucher.exit(&crate::ID)?;
+    }
+
+    token_inflow.exit(&crate::ID)?;
+  }
+
+  Ok(())
+}
+
+/// Settles every token inflow and empties the `escrowed_amount` of each of `wallet_share`'s
+/// vouchers, either into its `pending_amount` or back to the pool when `forfeit` is set. Forfeited
+/// amounts are split like new inflow, so the member still gets their cut of it. Takes the same
+/// accounts as `settle_wallet_share`.
+pub fn release_wallet_share<'info>(
+  fanout: &Account<'info, FanoutV0>,
+  wallet_share: &Pubkey,
+  forfeit: bool,
+  accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<EscrowReleasedV0>> {
+  require_eq!(
+    accounts.len(),
+    fanout.num_inflows as usize * 3,
+    ErrorCode::InvalidInflowAccounts
+  );
+
+  let unix_ts = Clock::get()?.unix_timestamp;
+  let mut settled = Vec::with_capacity(fanout.num_inflows as usize);
+  let mut released = vec![];
+  for triple in accounts.chunks(3) {
+    let mut token_inflow = settle_token_inflow(fanout, &triple[0], &triple[1], &mut settled)?;
+    if let Some(mut voucher) =
+      wallet_share_voucher(&fanout.key(), &token_inflow, wallet_share, &triple[2])?
+    {
+      // Whatever accrued since the last claim was also held back
+      voucher.withhold_claim(&token_inflow);
+      let amount = std::mem::take(&mut voucher.escrowed_amount);
+      if forfeit {
+        token_inflow.redistribute(amount, fanout);
+      } else {
+        voucher.add_pending(amount, unix_ts);
+      }
+      released.push(EscrowReleasedV0 {
+        fanout: fanout.key(),
+        voucher: voucher.key(),
+        mint: voucher.mint,
+        amount,
+        forfeited: forfeit,
+      });
+      voucher.exit(&crate::ID)?;
+    }
+
+    token_inflow.exit(&crate::ID)?;
+  }
+
+  Ok(released)
+}
@@ -7,6 +7,39 @@ pub struct GlobalStateV0 {
   pub authority: Pubkey,
   pub task_queue: Pubkey,
   pub bump: u8,
+  /// Authority proposed by the current authority, which takes over once it accepts
+  pub pending_authority: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransferFeePolicy {
+  /// Members receive their share net of any Token-2022 transfer fee
+  #[default]
+  Members,
+  /// Transfers are grossed up so members receive their full share, as long as the fee can be paid
+  /// out of the inflow's `retained_amount`. The extra fee is recovered from future inflow, so it is
+  /// shared by the pool
+  Pool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnissuedSharePolicy {
+  /// Inflow is split over the issued shares, so members collect the unissued portion
+  #[default]
+  Rebase,
+  /// The unissued portion is owed to this wallet and paid out by claim_treasury_v0
+  Treasury(Pubkey),
+  /// The unissued portion stays in the fanout. Anything a previous treasury was still owed is paid
+  /// to the authority by claim_treasury_v0
+  Retain,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForfeitDestination {
+  /// Split like new inflow over the fanout's shares, including the voucher's own if it has any
+  Redistribute,
+  /// Owed to the treasury of the fanout's unissued share policy, paid out by claim_treasury_v0
+  Treasury,
 }
 
 // ["fanout", hash(name)]
@@ -16,9 +49,9 @@ pub struct FanoutV0 {
   /// The authority that can modify the fanout configuration
   pub authority: Pubkey,
   pub cron_job: Pubkey,
-  /// Total SOL that has flowed into this fanout
+  /// Deprecated. SOL inflows are tracked on the native `TokenInflowV0` (mint = `Pubkey::default()`)
   pub total_inflow: u64,
-  /// Last snapshotted amount for calculating distributions
+  /// Deprecated. SOL inflows are tracked on the native `TokenInflowV0` (mint = `Pubkey::default()`)
   pub last_snapshot_amount: u64,
   /// Total shares across all members
   pub total_shares: u32,
@@ -36,9 +69,33 @@ pub struct FanoutV0 {
   pub queue_authority_bump: u8,
   /// Number of active token inflows for this fanout
   pub num_inflows: u32,
+  /// Who absorbs transfer fees on Token-2022 mints with the transfer fee extension
+  pub transfer_fee_policy: TransferFeePolicy,
+  /// Authority proposed by the current authority, which takes over once it accepts
+  pub pending_authority: Option<Pubkey>,
+  /// Schedule the cron job is recreated with by migrate_fanout_cron_job_v0
+  pub pending_schedule: Option<String>,
+  /// What happens to the portion of inflow belonging to shares that haven't been issued
+  pub unissued_share_policy: UnissuedSharePolicy,
+  /// Stops every payout from the fanout while inflow keeps accruing
+  pub paused: bool,
+  /// Seconds a voucher's pending amount can go undelivered before the authority can forfeit it with
+  /// forfeit_voucher_v0. Pending amounts never expire without one
+  pub claim_window_secs: Option<u64>,
 }
 
 impl FanoutV0 {
+  /// Who claim_treasury_v0 pays an inflow's `treasury_amount` to. Under Retain that is whatever a
+  /// previous treasury was still owed, which goes to the authority. Under Rebase it is folded back
+  /// into the pool once shares are issued instead
+  pub fn treasury(&self) -> Option<Pubkey> {
+    match self.unissued_share_policy {
+      UnissuedSharePolicy::Rebase => None,
+      UnissuedSharePolicy::Treasury(treasury) => Some(treasury),
+      UnissuedSharePolicy::Retain => Some(self.authority),
+    }
+  }
+
   pub fn get_next_cron_transaction_id(&mut self) -> u32 {
     if let Some(index) = self.available_cron_transaction_ids.pop() {
       index
@@ -68,7 +125,10 @@ impl FanoutV0 {
   }
 }
 
+pub const TWELVE_PREC: u128 = 1_000000000000;
+
 // ["token_inflow", fanout, mint]
+// SOL is tracked with mint = Pubkey::default()
 #[account]
 #[derive(Default)]
 pub struct TokenInflowV0 {
@@ -76,7 +136,8 @@ pub struct TokenInflowV0 {
   pub fanout: Pubkey,
   /// The mint of the token being tracked
   pub mint: Pubkey,
-  /// Total amount of tokens that have flowed into this fanout
+  /// Total amount of tokens that have flowed into this fanout, net of recovered transfer fees.
+  /// Before `acc_per_share` was introduced this was denominated in `total_shares`
   pub total_inflow: u64,
   /// Last snapshotted amount for calculating distributions
   pub last_snapshot_amount: u64,
@@ -85,26 +146,135 @@ pub struct TokenInflowV0 {
   pub rent_refund: Pubkey,
   /// Number of active vouchers for this token inflow
   pub num_vouchers: u32,
+  /// Transfer fees paid by the pool on behalf of members that have not yet been recovered from inflow
+  pub unrecovered_fees: u64,
+  /// The share count the legacy `total_inflow` was denominated in. Only used to convert existing
+  /// inflows and vouchers to `acc_per_share`
+  pub total_shares: u32,
+  /// Amount owed per issued share since this inflow was created, scaled by `TWELVE_PREC`
+  pub acc_per_share: u128,
+  /// Whether `acc_per_share` is in use. Inflows created before it existed are converted by
+  /// migrate_accumulator_v0 or the first time they are updated
+  pub accumulator_initialized: bool,
+  /// Unissued portion of inflow owed to the fanout's treasury, still held by the fanout. Under Rebase
+  /// it holds inflow that arrived before any shares were issued
+  pub treasury_amount: u64,
+  /// Stops payouts of this mint only, see `FanoutV0::paused`
+  pub paused: bool,
+  /// Cron claims skip vouchers owed less than this, leaving it to accrue
+  pub min_payout: u64,
+  /// Runs this inflow's claims on their own schedule instead of the fanout's cron job
+  pub cron_job: Option<Pubkey>,
+  /// Part of the balance owed to neither members nor the treasury, e.g. the unissued portion under
+  /// `UnissuedSharePolicy::Retain`. Transfer fees are only covered by the pool out of this
+  pub retained_amount: u64,
 }
 
 impl TokenInflowV0 {
+  /// Whether this inflow tracks the fanout's own lamports rather than an SPL token
+  pub fn is_native(&self) -> bool {
+    self.mint == Pubkey::default()
+  }
+
+  /// The cron job vouchers of this inflow are scheduled on
+  pub fn voucher_cron_job(&self, fanout: &FanoutV0) -> Pubkey {
+    self.cron_job.unwrap_or(fanout.cron_job)
+  }
+
+  /// Whether `cron_job` may hold claims of this inflow. Vouchers scheduled before the inflow got its
+  /// own cron job stay on the fanout's until they are moved
+  pub fn runs_on(&self, fanout: &FanoutV0, cron_job: &Pubkey) -> bool {
+    *cron_job == fanout.cron_job || self.cron_job == Some(*cron_job)
+  }
+
+  /// Whether payouts of this inflow are stopped, either for this mint or the whole fanout
+  pub fn is_paused(&self, fanout: &FanoutV0) -> bool {
+    self.paused || fanout.paused
+  }
+
+  /// Converts an inflow tracked by the legacy `total_inflow` to `acc_per_share`. `total_inflow`
+  /// was denominated in `total_shares`, so it is already an amount per share.
+  pub fn initialize_accumulator(&mut self, fanout: &FanoutV0) {
+    if self.accumulator_initialized {
+      return;
+    }
+    // Inflows created before total shares could change are denominated in the fanout's total shares
+    if self.total_shares == 0 {
+      self.total_shares = fanout.total_shares;
+    }
+    self.acc_per_share = (self.total_inflow as u128)
+      .checked_mul(TWELVE_PREC)
+      .unwrap()
+      .checked_div(self.total_shares as u128)
+      .unwrap_or(0);
+    self.accumulator_initialized = true;
+  }
+
+  /// Credits `amount`, already held by the fanout, to the pool again as if it had just arrived.
+  /// Expects the inflow to already be updated to the current balance.
+  pub fn redistribute(&mut self, amount: u64, fanout: &FanoutV0) {
+    let balance = self.last_snapshot_amount;
+    self.last_snapshot_amount = balance.checked_sub(amount).unwrap();
+    self.update_total_inflow(balance, fanout);
+  }
+
   pub fn update_total_inflow(&mut self, new_balance: u64, fanout: &FanoutV0) {
+    self.initialize_accumulator(fanout);
     let tsi = fanout.total_shares_issued;
-    let shares_diff = fanout.total_shares.checked_sub(tsi).unwrap();
 
-    let new_inflow = new_balance.checked_sub(self.last_snapshot_amount).unwrap();
-    let unissued_correction_factor = (new_inflow as u128)
-      .checked_mul(shares_diff as u128)
-      .unwrap()
-      .checked_div(tsi as u128)
-      .unwrap_or_default();
-    self.total_inflow = self
-      .total_inflow
-      .checked_add(new_inflow)
+    let mut new_inflow = new_balance.checked_sub(self.last_snapshot_amount).unwrap();
+    // Fees the pool paid on behalf of members come out of new inflow before anyone is credited
+    let recovered_fees = new_inflow.min(self.unrecovered_fees);
+    self.unrecovered_fees -= recovered_fees;
+    self.retained_amount = self.retained_amount.checked_add(recovered_fees).unwrap();
+    new_inflow -= recovered_fees;
+    self.total_inflow = self.total_inflow.checked_add(new_inflow).unwrap();
+    self.last_snapshot_amount = new_balance;
+
+    let denominator = match fanout.unissued_share_policy {
+      UnissuedSharePolicy::Rebase => tsi,
+      UnissuedSharePolicy::Treasury(_) | UnissuedSharePolicy::Retain => fanout.total_shares,
+    };
+    // With no shares issued under Rebase there is nobody to credit yet, so it waits with the treasury
+    if denominator == 0 {
+      self.treasury_amount = self.treasury_amount.checked_add(new_inflow).unwrap();
+      return;
+    }
+    // Only folded in once there are shares to credit it to, so it isn't dropped in the meantime. Under
+    // the other policies it stays owed to `FanoutV0::treasury`
+    if fanout.unissued_share_policy == UnissuedSharePolicy::Rebase {
+      new_inflow = new_inflow.checked_add(self.treasury_amount).unwrap();
+      self.treasury_amount = 0;
+    }
+
+    // New inflow is split over the denominator's shares. Rounding only ever leaves dust in the fanout
+    self.acc_per_share = self
+      .acc_per_share
+      .checked_add(
+        (new_inflow as u128)
+          .checked_mul(TWELVE_PREC)
+          .unwrap()
+          .checked_div(denominator as u128)
+          .unwrap(),
+      )
+      .unwrap();
+    // Rounded up, since members carry their rounding over between claims
+    let issued_amount: u64 = (new_inflow as u128)
+      .checked_mul(tsi as u128)
       .unwrap()
-      .checked_add(unissued_correction_factor as u64)
+      .div_ceil(denominator as u128)
+      .try_into()
       .unwrap();
-    self.last_snapshot_amount = new_balance;
+    let unissued_amount = new_inflow.checked_sub(issued_amount).unwrap();
+    match fanout.unissued_share_policy {
+      UnissuedSharePolicy::Rebase => {}
+      UnissuedSharePolicy::Treasury(_) => {
+        self.treasury_amount = self.treasury_amount.checked_add(unissued_amount).unwrap();
+      }
+      UnissuedSharePolicy::Retain => {
+        self.retained_amount = self.retained_amount.checked_add(unissued_amount).unwrap();
+      }
+    }
   }
 }
 
@@ -121,6 +291,40 @@ pub struct WalletShareV0 {
   pub shares: u32,
   /// The account that will receive rent when this account is closed
   pub rent_refund: Pubkey,
+  /// Holds back this wallet's payouts in each voucher's `escrowed_amount` until unsuspended
+  pub suspended: bool,
+}
+
+impl WalletShareV0 {
+  /// Size of wallet shares created before they could be suspended, which end right before
+  /// `suspended`
+  pub const LEGACY_LEN: usize = 8 + 104;
+
+  /// Deserializes a wallet share, reading ones created before they could be suspended as not
+  /// suspended. Those have to be grown with migrate_wallet_share_v0 before they can be written to
+  pub fn try_deserialize_any(data: &[u8]) -> Result<Self> {
+    if data.len() == Self::LEGACY_LEN {
+      let mut padded = data.to_vec();
+      padded.push(0);
+      return Self::try_deserialize(&mut &padded[..]);
+    }
+    Self::try_deserialize(&mut &data[..])
+  }
+
+  /// Loads `wallet_share` with `try_deserialize_any`. The caller checks that it is owned by this
+  /// program
+  pub fn load(wallet_share: &AccountInfo) -> Result<Self> {
+    Self::try_deserialize_any(&wallet_share.try_borrow_data()?)
+  }
+
+  /// Whether `wallet_share` is suspended. Closed wallet shares never are, as they can't be closed
+  /// while suspended
+  pub fn is_suspended(wallet_share: &AccountInfo) -> Result<bool> {
+    if wallet_share.data_is_empty() {
+      return Ok(false);
+    }
+    Ok(Self::load(wallet_share)?.suspended)
+  }
 }
 
 #[account]
@@ -142,6 +346,208 @@ pub struct VoucherV0 {
   pub shares: u32,
   /// The account that will receive rent when this account is closed
   pub rent_refund: Pubkey,
+  /// Total amount sent from the fanout for this voucher, including transfer fees
+  pub total_gross_claimed: u64,
+  /// Total amount received by the wallet, after transfer fees
+  pub total_net_claimed: u64,
+  /// Amount owed to the wallet that could not be delivered, still held by the fanout
+  pub pending_amount: u64,
+  /// `shares * acc_per_share` already paid out or settled, scaled by `TWELVE_PREC`. Whatever is
+  /// left over below one token stays owed and carries over to the next claim
+  pub reward_debt: u128,
+  /// Whether `reward_debt` is in use. Vouchers created before it existed are converted by
+  /// migrate_accumulator_v0 or the first time they are claimed
+  pub accumulator_initialized: bool,
+  /// Total amount owed to the wallet that has been paid out, before transfer fees. Vouchers resized
+  /// by migrate_voucher_v0 only count payouts made after the migration
+  pub total_claimed: u64,
+  /// Number of payouts made for this voucher
+  pub claim_count: u32,
+  pub last_claim_unix_ts: i64,
+  pub last_claim_slot: u64,
+  /// Amount held back while the wallet share was suspended. Released into `pending_amount` or
+  /// forfeited to the pool when it is unsuspended
+  pub escrowed_amount: u64,
+  /// When `pending_amount` last went from nothing to something, which starts the fanout's claim
+  /// window
+  pub pending_since_unix_ts: i64,
+  /// Raises the inflow's `min_payout` for this voucher, set by its wallet
+  pub min_payout: u64,
+}
+
+impl VoucherV0 {
+  /// Size of vouchers created before any fields were added to the baseline layout
+  pub const LEGACY_LEN: usize = 8 + 60 + 184;
+
+  /// Deserializes a voucher, reading ones not yet grown by migrate_voucher_v0 as they read once
+  /// migrated. Those have to be migrated before they can be claimed, rotated or closed
+  pub fn try_deserialize_any(data: &[u8]) -> Result<Self> {
+    let len = 8 + 60 + std::mem::size_of::<Self>();
+    if data.len() < len {
+      let mut padded = data.to_vec();
+      padded.resize(len, 0);
+      return Self::try_deserialize(&mut &padded[..]);
+    }
+    Self::try_deserialize(&mut &data[..])
+  }
+
+  /// Converts a voucher tracked by the legacy `last_claimed_inflow` and `total_dust` to
+  /// `reward_debt`. Expects `token_inflow` to already be converted.
+  pub fn initialize_accumulator(&mut self, token_inflow: &TokenInflowV0) {
+    if self.accumulator_initialized {
+      return;
+    }
+    let claimed_per_share = (self.last_claimed_inflow as u128)
+      .checked_mul(TWELVE_PREC)
+      .unwrap()
+      .checked_div(token_inflow.total_shares as u128)
+      .unwrap_or(0);
+    self.reward_debt = (self.shares as u128)
+      .checked_mul(claimed_per_share)
+      .unwrap()
+      .saturating_sub(self.total_dust as u128);
+    self.total_dust = 0;
+    self.accumulator_initialized = true;
+  }
+
+  fn accrued(&self, token_inflow: &TokenInflowV0) -> u128 {
+    (self.shares as u128)
+      .checked_mul(token_inflow.acc_per_share)
+      .unwrap()
+  }
+
+  /// The amount owed to this voucher that has not been claimed or settled, in whole tokens
+  pub fn unclaimed(&self, token_inflow: &TokenInflowV0) -> u64 {
+    (self
+      .accrued(token_inflow)
+      .checked_sub(self.reward_debt)
+      .unwrap()
+      / TWELVE_PREC)
+      .try_into()
+      .unwrap()
+  }
+
+  /// The amount owed to this voucher below one token, scaled by `TWELVE_PREC`
+  pub fn dust(&self, token_inflow: &TokenInflowV0) -> u128 {
+    self
+      .accrued(token_inflow)
+      .checked_sub(self.reward_debt)
+      .unwrap()
+      % TWELVE_PREC
+  }
+
+  /// Computes the amount owed to this voucher since its last claim and marks it as paid, carrying
+  /// over anything below one token. Expects `token_inflow` to already be updated to the current
+  /// balance.
+  pub fn calculate_claim(&mut self, token_inflow: &TokenInflowV0) -> u64 {
+    self.initialize_accumulator(token_inflow);
+    let dist_amount = self.unclaimed(token_inflow);
+    self.reward_debt = self
+      .reward_debt
+      .checked_add((dist_amount as u128).checked_mul(TWELVE_PREC).unwrap())
+      .unwrap();
+
+    msg!(
+      "Acc per share: {}, dist amount: {}",
+      token_inflow.acc_per_share,
+      dist_amount
+    );
+
+    dist_amount
+  }
+
+  /// Whether everything owed, including the pending amount, is below the payout threshold of the
+  /// inflow or the voucher's own. Expects `token_inflow` to already be updated to the current
+  /// balance.
+  pub fn below_min_payout(&mut self, token_inflow: &TokenInflowV0) -> bool {
+    let min_payout = token_inflow.min_payout.max(self.min_payout);
+    if min_payout == 0 {
+      return false;
+    }
+    self.owed(token_inflow) < min_payout
+  }
+
+  /// Everything owed to this voucher, including the pending amount. Expects `token_inflow` to
+  /// already be updated to the current balance.
+  pub fn owed(&mut self, token_inflow: &TokenInflowV0) -> u64 {
+    self.initialize_accumulator(token_inflow);
+    self
+      .unclaimed(token_inflow)
+      .saturating_add(self.pending_amount)
+  }
+
+  /// Adds `amount` to `pending_amount`, starting the claim window if nothing was pending
+  pub fn add_pending(&mut self, amount: u64, unix_ts: i64) {
+    if amount == 0 {
+      return;
+    }
+    if self.pending_amount == 0 {
+      self.pending_since_unix_ts = unix_ts;
+    }
+    self.pending_amount = self.pending_amount.checked_add(amount).unwrap();
+  }
+
+  /// When the pending amount can be forfeited under `fanout`'s claim window, if it can at all.
+  /// Pending amounts from before `pending_since_unix_ts` was tracked count from the last payout
+  pub fn pending_expires_unix_ts(&self, fanout: &FanoutV0) -> Option<i64> {
+    let claim_window_secs = fanout.claim_window_secs?;
+    if self.pending_amount == 0 {
+      return None;
+    }
+    Some(
+      self
+        .pending_since_unix_ts
+        .max(self.last_claim_unix_ts)
+        .saturating_add(claim_window_secs.try_into().unwrap_or(i64::MAX)),
+    )
+  }
+
+  /// Moves what is owed at the current share count into `pending_amount`, for the voucher's wallet
+  /// to withdraw later. Expects `token_inflow` to already be updated to the current balance.
+  pub fn defer_claim(&mut self, token_inflow: &TokenInflowV0, unix_ts: i64) {
+    let owed = self.calculate_claim(token_inflow);
+    self.add_pending(owed, unix_ts);
+    self.last_claimed_inflow = token_inflow.total_inflow;
+  }
+
+  /// Moves what is owed at the current share count into `escrowed_amount` while the wallet share is
+  /// suspended, returning the amount moved. Expects `token_inflow` to already be updated to the
+  /// current balance.
+  pub fn withhold_claim(&mut self, token_inflow: &TokenInflowV0) -> u64 {
+    let owed = self.calculate_claim(token_inflow);
+    self.escrowed_amount = self.escrowed_amount.checked_add(owed).unwrap();
+    self.last_claimed_inflow = token_inflow.total_inflow;
+    owed
+  }
+
+  /// Defers or withholds what is owed, depending on whether the wallet share is suspended
+  pub fn hold_claim(&mut self, token_inflow: &TokenInflowV0, suspended: bool, unix_ts: i64) {
+    if suspended {
+      self.withhold_claim(token_inflow);
+    } else {
+      self.defer_claim(token_inflow, unix_ts);
+    }
+  }
+
+  /// Switches to `shares`, keeping whatever is owed below one token. Anything owed at the old share
+  /// count must be claimed or settled first.
+  pub fn set_shares(&mut self, shares: u32, token_inflow: &TokenInflowV0) {
+    self.initialize_accumulator(token_inflow);
+    let carried = self
+      .accrued(token_inflow)
+      .checked_sub(self.reward_debt)
+      .unwrap();
+    self.shares = shares;
+    self.reward_debt = self.accrued(token_inflow).saturating_sub(carried);
+  }
+
+  /// Records a payout of `amount` owed to the wallet
+  pub fn record_claim(&mut self, amount: u64, clock: &Clock) {
+    self.total_claimed = self.total_claimed.checked_add(amount).unwrap();
+    self.claim_count = self.claim_count.checked_add(1).unwrap();
+    self.last_claim_unix_ts = clock.unix_timestamp;
+    self.last_claim_slot = clock.slot;
+  }
 }
 
 #[macro_export]
@@ -161,3 +567,296 @@ macro_rules! queue_authority_seeds {
     &[b"queue_authority", &[$fanout.queue_authority_bump]]
   };
 }
+
+#[cfg(test)]
+mod tests {
+  use proptest::prelude::*;
+
+  use super::*;
+
+  #[derive(Clone, Debug)]
+  enum Op {
+    Inflow(u64),
+    Claim(usize),
+    SetShares(usize, u32),
+    SetPolicy(UnissuedSharePolicy),
+  }
+
+  fn op(num_vouchers: usize) -> impl Strategy<Value = Op> {
+    prop_oneof![
+      (0..1_000_000_000_000u64).prop_map(Op::Inflow),
+      (0..num_vouchers).prop_map(Op::Claim),
+      (0..num_vouchers, 0..1_000_000u32).prop_map(|(i, s)| Op::SetShares(i, s)),
+      policy().prop_map(Op::SetPolicy),
+    ]
+  }
+
+  fn policy() -> impl Strategy<Value = UnissuedSharePolicy> {
+    prop_oneof![
+      Just(UnissuedSharePolicy::Rebase),
+      Just(UnissuedSharePolicy::Treasury(Pubkey::default())),
+      Just(UnissuedSharePolicy::Retain),
+    ]
+  }
+
+  fn scenario() -> impl Strategy<Value = (Vec<u32>, Vec<Op>)> {
+    prop::collection::vec(0..1_000_000u32, 1..8).prop_flat_map(|shares| {
+      (
+        Just(shares.clone()),
+        prop::collection::vec(op(shares.len()), 1..64),
+      )
+    })
+  }
+
+  /// Token inflows created before any fields were added were allocated for the baseline layout
+  const LEGACY_TOKEN_INFLOW_LEN: usize = 8 + 60 + 120;
+
+  #[test]
+  fn cron_job_fits_legacy_token_inflow_after_resize() {
+    let mut data = vec![0u8; LEGACY_TOKEN_INFLOW_LEN];
+    TokenInflowV0 {
+      mint: Pubkey::new_unique(),
+      num_vouchers: 2,
+      ..Default::default()
+    }
+    .try_serialize(&mut &mut data[..])
+    .unwrap();
+
+    let mut token_inflow = TokenInflowV0::try_deserialize(&mut &data[..]).unwrap();
+    assert_eq!(token_inflow.cron_job, None);
+    token_inflow.cron_job = Some(Pubkey::new_unique());
+    assert!(token_inflow.try_serialize(&mut &mut data[..]).is_err());
+
+    data.resize(crate::resize_to_fit::fitted_size(&token_inflow).unwrap(), 0);
+    token_inflow.try_serialize(&mut &mut data[..]).unwrap();
+    let resized = TokenInflowV0::try_deserialize(&mut &data[..]).unwrap();
+    assert_eq!(resized.cron_job, token_inflow.cron_job);
+    assert_eq!(resized.num_vouchers, 2);
+  }
+
+  #[test]
+  fn legacy_voucher_claims_after_migration() {
+    let voucher = VoucherV0 {
+      wallet: Pubkey::new_unique(),
+      shares: 10,
+      last_claimed_inflow: 500,
+      ..Default::default()
+    };
+    let mut serialized = vec![];
+    voucher.try_serialize(&mut serialized).unwrap();
+    let mut data = serialized[..8 + 184].to_vec();
+    data.resize(VoucherV0::LEGACY_LEN, 0);
+    assert!(VoucherV0::try_deserialize(&mut &data[..]).is_err());
+    let legacy = VoucherV0::try_deserialize_any(&data).unwrap();
+    assert_eq!(legacy.wallet, voucher.wallet);
+    assert_eq!(legacy.last_claimed_inflow, 500);
+
+    // As migrate_voucher_v0 reallocates it
+    data.resize(8 + 60 + std::mem::size_of::<VoucherV0>(), 0);
+    let mut migrated = VoucherV0::try_deserialize(&mut &data[..]).unwrap();
+    assert_eq!(migrated.wallet, voucher.wallet);
+    assert_eq!(migrated.last_claimed_inflow, 500);
+    assert!(!migrated.accumulator_initialized);
+    assert_eq!(migrated.total_claimed, 0);
+
+    migrated.total_claimed = u64::MAX;
+    migrated.claim_count = u32::MAX;
+    migrated.last_claim_unix_ts = i64::MAX;
+    migrated.last_claim_slot = u64::MAX;
+    migrated.min_payout = u64::MAX;
+    migrated.try_serialize(&mut &mut data[..]).unwrap();
+    let claimed = VoucherV0::try_deserialize(&mut &data[..]).unwrap();
+    assert_eq!(claimed.min_payout, u64::MAX);
+  }
+
+  #[test]
+  fn legacy_wallet_share_reads_as_not_suspended() {
+    let wallet_share = WalletShareV0 {
+      wallet: Pubkey::new_unique(),
+      shares: 10,
+      ..Default::default()
+    };
+    let mut data = vec![];
+    wallet_share.try_serialize(&mut data).unwrap();
+    data.truncate(WalletShareV0::LEGACY_LEN);
+
+    assert!(WalletShareV0::try_deserialize(&mut &data[..]).is_err());
+    let legacy = WalletShareV0::try_deserialize_any(&data).unwrap();
+    assert_eq!(legacy.wallet, wallet_share.wallet);
+    assert_eq!(legacy.shares, 10);
+    assert!(!legacy.suspended);
+  }
+
+  #[test]
+  fn rebase_keeps_treasury_amount_until_shares_are_issued() {
+    let mut fanout = FanoutV0 {
+      total_shares: 100,
+      unissued_share_policy: UnissuedSharePolicy::Rebase,
+      ..Default::default()
+    };
+    let mut token_inflow = TokenInflowV0 {
+      accumulator_initialized: true,
+      treasury_amount: 500,
+      last_snapshot_amount: 500,
+      ..Default::default()
+    };
+
+    // Inflow that arrives with no shares issued waits along with it
+    token_inflow.update_total_inflow(800, &fanout);
+    assert_eq!(token_inflow.treasury_amount, 800);
+    assert_eq!(token_inflow.acc_per_share, 0);
+
+    fanout.total_shares_issued = 50;
+    let mut voucher = VoucherV0 {
+      shares: 50,
+      accumulator_initialized: true,
+      ..Default::default()
+    };
+    token_inflow.update_total_inflow(800, &fanout);
+    assert_eq!(token_inflow.treasury_amount, 0);
+    assert_eq!(voucher.calculate_claim(&token_inflow), 800);
+  }
+
+  #[test]
+  fn retain_keeps_treasury_amount_for_the_authority() {
+    let mut fanout = FanoutV0 {
+      authority: Pubkey::new_unique(),
+      total_shares: 100,
+      total_shares_issued: 50,
+      unissued_share_policy: UnissuedSharePolicy::Treasury(Pubkey::new_unique()),
+      ..Default::default()
+    };
+    let mut token_inflow = TokenInflowV0 {
+      accumulator_initialized: true,
+      ..Default::default()
+    };
+    token_inflow.update_total_inflow(1000, &fanout);
+    assert_eq!(token_inflow.treasury_amount, 500);
+
+    fanout.unissued_share_policy = UnissuedSharePolicy::Retain;
+    token_inflow.update_total_inflow(2000, &fanout);
+    assert_eq!(token_inflow.treasury_amount, 500);
+    assert_eq!(token_inflow.retained_amount, 500);
+    assert_eq!(fanout.treasury(), Some(fanout.authority));
+  }
+
+  proptest! {
+    #[test]
+    fn payouts_never_exceed_inflow(
+      (shares, ops) in scenario(),
+      policy in policy(),
+      unissued in any::<bool>(),
+    ) {
+      // Starting with no shares issued covers inflow that arrives before anyone can be credited
+      let shares: Vec<u32> = if unissued {
+        vec![0; shares.len()]
+      } else {
+        shares
+      };
+      let mut fanout = FanoutV0 {
+        total_shares: 8_000_000,
+        total_shares_issued: shares.iter().sum(),
+        unissued_share_policy: policy,
+        ..Default::default()
+      };
+      let mut token_inflow = TokenInflowV0 {
+        accumulator_initialized: true,
+        ..Default::default()
+      };
+      let mut vouchers: Vec<VoucherV0> = shares
+        .iter()
+        .map(|&shares| VoucherV0 {
+          shares,
+          accumulator_initialized: true,
+          ..Default::default()
+        })
+        .collect();
+
+      let mut balance = 0u64;
+      let mut total_inflow = 0u64;
+      let mut total_paid = 0u64;
+      let mut inflow_events = 0u64;
+      for op in ops {
+        match op {
+          Op::Inflow(amount) => {
+            balance += amount;
+            total_inflow += amount;
+            inflow_events += 1;
+          }
+          Op::Claim(i) => {
+            token_inflow.update_total_inflow(balance, &fanout);
+            let amount = vouchers[i].calculate_claim(&token_inflow) + vouchers[i].pending_amount;
+            vouchers[i].pending_amount = 0;
+            balance -= amount;
+            total_paid += amount;
+            token_inflow.last_snapshot_amount = balance;
+          }
+          Op::SetShares(i, new_shares) => {
+            token_inflow.update_total_inflow(balance, &fanout);
+            let owed = vouchers[i].calculate_claim(&token_inflow);
+            vouchers[i].pending_amount += owed;
+            fanout.total_shares_issued = fanout.total_shares_issued - vouchers[i].shares + new_shares;
+            vouchers[i].set_shares(new_shares, &token_inflow);
+          }
+          Op::SetPolicy(policy) => {
+            // As update_unissued_share_policy_v0 settles under the old policy first
+            token_inflow.update_total_inflow(balance, &fanout);
+            fanout.unissued_share_policy = policy;
+          }
+        }
+        let settled: u64 = vouchers.iter().map(|v| v.pending_amount).sum();
+        prop_assert!(
+          total_paid + settled + token_inflow.treasury_amount + token_inflow.retained_amount
+            <= total_inflow
+        );
+      }
+
+      // Once everyone claims, at most rounding dust is left behind
+      token_inflow.update_total_inflow(balance, &fanout);
+      for voucher in vouchers.iter_mut() {
+        total_paid += voucher.calculate_claim(&token_inflow) + voucher.pending_amount;
+      }
+      total_paid += token_inflow.treasury_amount + token_inflow.retained_amount;
+      prop_assert!(total_paid <= total_inflow);
+      if fanout.total_shares_issued > 0 {
+        prop_assert!(total_inflow - total_paid <= inflow_events + vouchers.len() as u64 * 2);
+      }
+    }
+
+    #[test]
+    fn migration_preserves_owed_amounts(
+      total_shares in 1..1_000_000u32,
+      shares in 0..1_000_000u32,
+      last_claimed_inflow in 0..1_000_000_000_000u64,
+      unclaimed in 0..1_000_000_000_000u64,
+      total_dust in 0..1_000_000_000_000u64,
+    ) {
+      let shares = shares.min(total_shares);
+      let fanout = FanoutV0 {
+        total_shares,
+        ..Default::default()
+      };
+      let mut token_inflow = TokenInflowV0 {
+        total_inflow: last_claimed_inflow + unclaimed,
+        ..Default::default()
+      };
+      let mut voucher = VoucherV0 {
+        shares,
+        last_claimed_inflow,
+        total_dust,
+        ..Default::default()
+      };
+      let legacy_owed = ((unclaimed as u128 * shares as u128 * TWELVE_PREC / total_shares as u128
+        + total_dust as u128)
+        / TWELVE_PREC) as u64;
+
+      token_inflow.initialize_accumulator(&fanout);
+      voucher.initialize_accumulator(&token_inflow);
+      let owed = voucher.calculate_claim(&token_inflow);
+
+      // Rounding the legacy amounts per share can only cost the voucher under one token
+      prop_assert!(owed <= legacy_owed);
+      prop_assert!(legacy_owed - owed <= 1);
+    }
+  }
+}
@@ -5,12 +5,12 @@ import { cronJobKey, cronJobNameMappingKey, init as initCron, userCronJobsKey, P
 import { Tuktuk } from "@helium/tuktuk-idls/lib/types/tuktuk";
 import { init as initTuktuk, nextAvailableTaskIds, runTask, taskKey, taskQueueKey, taskQueueNameMappingKey, tuktukConfigKey } from "@helium/tuktuk-sdk";
 import { ComputeBudgetInstruction, ComputeBudgetProgram, Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
-import { fanoutKey, globalStateKey, init, PROGRAM_ID, queueAuthorityKey, tokenInflowKey } from "../packages/wallet-fanout-sdk/src";
+import { ataPayerKey, fanoutKey, globalStateKey, init, PROGRAM_ID, queueAuthorityKey, tokenInflowKey, voucherKey } from "../packages/wallet-fanout-sdk/src";
 import { WalletFanout } from "../target/types/wallet_fanout";
 import { expect } from "chai";
 import { createAtaAndMint, createMint, sendInstructions } from "@helium/spl-utils";
 import { execSync } from "child_process";
-import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { createAssociatedTokenAccountIdempotentInstruction, createBurnInstruction, createCloseAccountInstruction, createFreezeAccountInstruction, createInitializeMintInstruction, createInitializeTransferFeeConfigInstruction, createMintToInstruction, createTransferCheckedWithFeeInstruction, ExtensionType, getAccount, getAssociatedTokenAddressSync, getMint, getMintLen, getTransferFeeConfig, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 export const ANCHOR_PATH = "anchor";
 
@@ -110,6 +110,84 @@ describe("fanout", () => {
   })
 
 
+  it("should hand off the global state authority in two steps", async () => {
+    const newAuthority = Keypair.generate()
+    const globalState = globalStateKey(PROGRAM_ID)[0]
+    await program.methods.updateGlobalStateV0({
+      pendingAuthority: newAuthority.publicKey,
+    })
+      .accounts({
+        authority: me,
+        taskQueue: null,
+        taskQueueAuthority: null,
+      })
+      .rpc()
+    await program.methods.acceptGlobalStateAuthorityV0()
+      .accounts({
+        pendingAuthority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc()
+    expect((await program.account.globalStateV0.fetch(globalState)).authority.toBase58())
+      .to.equal(newAuthority.publicKey.toBase58())
+
+    // Hand it back so the rest of the suite keeps working
+    await program.methods.updateGlobalStateV0({
+      pendingAuthority: me,
+    })
+      .accounts({
+        authority: newAuthority.publicKey,
+        taskQueue: null,
+        taskQueueAuthority: null,
+      })
+      .signers([newAuthority])
+      .rpc()
+    await program.methods.acceptGlobalStateAuthorityV0()
+      .accounts({
+        pendingAuthority: me,
+      })
+      .rpc()
+    const globalStateAcc = await program.account.globalStateV0.fetch(globalState)
+    expect(globalStateAcc.authority.toBase58()).to.equal(me.toBase58())
+    expect(globalStateAcc.pendingAuthority).to.be.null
+  })
+
+  it("should cancel a pending global state authority", async () => {
+    const newAuthority = Keypair.generate()
+    const globalState = globalStateKey(PROGRAM_ID)[0]
+    await program.methods.updateGlobalStateV0({
+      pendingAuthority: newAuthority.publicKey,
+    })
+      .accounts({
+        authority: me,
+        taskQueue: null,
+        taskQueueAuthority: null,
+      })
+      .rpc()
+    await program.methods.updateGlobalStateV0({
+      pendingAuthority: null,
+    })
+      .accounts({
+        authority: me,
+        taskQueue: null,
+        taskQueueAuthority: null,
+      })
+      .rpc()
+    expect((await program.account.globalStateV0.fetch(globalState)).pendingAuthority).to.be.null
+
+    try {
+      await program.methods.acceptGlobalStateAuthorityV0()
+        .accounts({
+          pendingAuthority: newAuthority.publicKey,
+        })
+        .signers([newAuthority])
+        .rpc()
+      expect.fail("accept should fail once the proposal is cancelled")
+    } catch (e: any) {
+      expect(e.toString()).to.include("InvalidPendingAuthority")
+    }
+  })
+
   it("should initialize a fanout", async () => {
     const userCronJobsK = userCronJobsKey(queueAuthority)[0]
     const userCronJobs = await cronProgram.account.userCronJobsV0.fetchNullable(userCronJobsK)
@@ -221,6 +299,237 @@ describe("fanout", () => {
       expect(waleltShareAcc.wallet.toBase58()).to.equal(newWallet.publicKey.toBase58())
     })
 
+    it("should refund the rent freed when a fanout shrinks", async () => {
+      // Lamports the fanout holds above its rent exempt minimum
+      const distributable = async () => {
+        const info = (await provider.connection.getAccountInfo(fanout))!
+        return info.lamports - await provider.connection.getMinimumBalanceForRentExemption(info.data.length)
+      }
+      await program.methods.proposeFanoutAuthorityV0({
+        pendingAuthority: Keypair.generate().publicKey,
+      })
+        .accounts({
+          payer: me,
+          fanout,
+        })
+        .rpc()
+      const fanoutLamports = await provider.connection.getBalance(fanout)
+      const fanoutDistributable = await distributable()
+
+      // Cancelling the proposal drops the pending authority, so the payer gets that rent back
+      await program.methods.proposeFanoutAuthorityV0({
+        pendingAuthority: null,
+      })
+        .accounts({
+          payer: me,
+          fanout,
+        })
+        .rpc()
+      expect(await provider.connection.getBalance(fanout)).to.be.lessThan(fanoutLamports)
+      expect(await distributable()).to.equal(fanoutDistributable)
+    })
+
+    it("should transfer the fanout authority once the new authority accepts", async () => {
+      const newAuthority = Keypair.generate()
+      await program.methods.proposeFanoutAuthorityV0({
+        pendingAuthority: newAuthority.publicKey,
+      })
+        .accounts({
+          payer: me,
+          fanout,
+        })
+        .rpc()
+
+      let fanoutAcc = await program.account.fanoutV0.fetch(fanout)
+      expect(fanoutAcc.authority.toBase58()).to.equal(me.toBase58())
+      expect(fanoutAcc.pendingAuthority!.toBase58()).to.equal(newAuthority.publicKey.toBase58())
+
+      await program.methods.acceptFanoutAuthorityV0()
+        .accounts({
+          pendingAuthority: newAuthority.publicKey,
+          fanout,
+        })
+        .signers([newAuthority])
+        .rpc()
+
+      fanoutAcc = await program.account.fanoutV0.fetch(fanout)
+      expect(fanoutAcc.authority.toBase58()).to.equal(newAuthority.publicKey.toBase58())
+      expect(fanoutAcc.pendingAuthority).to.be.null
+    })
+
+    describe("with a transfer fee mint", () => {
+      const wallet1 = Keypair.generate()
+      const wallet2 = Keypair.generate()
+      let mint: PublicKey;
+      let fanoutAta: PublicKey;
+      let walletShare1: PublicKey;
+      let walletShare2: PublicKey;
+      let voucher1: PublicKey;
+      let voucher2: PublicKey;
+
+      function ata(owner: PublicKey) {
+        return getAssociatedTokenAddressSync(mint, owner, true, TOKEN_2022_PROGRAM_ID)
+      }
+
+      function settleAccounts(walletShare: PublicKey) {
+        return [
+          { pubkey: tokenInflowKey(fanout, mint)[0], isWritable: true, isSigner: false },
+          { pubkey: fanoutAta, isWritable: false, isSigner: false },
+          { pubkey: voucherKey(fanout, mint, walletShare)[0], isWritable: true, isSigner: false },
+        ]
+      }
+
+      // Sends `amount` into the fanout, which receives it less the 1% fee withheld in its account
+      async function sendToFanout(amount: number) {
+        await sendInstructions(provider, [
+          createMintToInstruction(mint, ata(me), me, amount, [], TOKEN_2022_PROGRAM_ID),
+          createTransferCheckedWithFeeInstruction(
+            ata(me), mint, fanoutAta, me, BigInt(amount), 8, BigInt(amount / 100), [], TOKEN_2022_PROGRAM_ID
+          ),
+        ])
+      }
+
+      async function claimTo(wallet: Keypair, voucher: PublicKey, walletShare: PublicKey) {
+        await program.methods.claimToV0()
+          .accounts({
+            wallet: wallet.publicKey,
+            fanout,
+            mint,
+            voucher,
+            walletShare,
+            destination: ata(wallet.publicKey),
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([wallet])
+          .rpc()
+      }
+
+      beforeEach(async () => {
+        const mintKeypair = Keypair.generate()
+        mint = mintKeypair.publicKey
+        fanoutAta = ata(fanout)
+        const mintLen = getMintLen([ExtensionType.TransferFeeConfig])
+        await sendInstructions(provider, [
+          SystemProgram.createAccount({
+            fromPubkey: me,
+            newAccountPubkey: mint,
+            space: mintLen,
+            lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          createInitializeTransferFeeConfigInstruction(mint, me, me, 100, BigInt(1000000000), TOKEN_2022_PROGRAM_ID),
+          createInitializeMintInstruction(mint, 8, me, null, TOKEN_2022_PROGRAM_ID),
+          ...[me, fanout, wallet1.publicKey, wallet2.publicKey].map((owner) =>
+            createAssociatedTokenAccountIdempotentInstruction(me, ata(owner), owner, mint, TOKEN_2022_PROGRAM_ID)
+          ),
+        ], [mintKeypair])
+        await sendToFanout(1000000)
+
+        const walletShares: PublicKey[] = []
+        for (const [index, wallet, shares] of [[0, wallet1, 10], [1, wallet2, 30]] as const) {
+          const { pubkeys: { walletShare } } = await program.methods.updateWalletShareV0({ shares, index })
+            .accounts({ payer: me, fanout, wallet: wallet.publicKey })
+            .rpcAndKeys()
+          walletShares.push(walletShare)
+        }
+        [walletShare1, walletShare2] = walletShares
+
+        const vouchers: PublicKey[] = []
+        for (const [id, walletShare] of walletShares.entries()) {
+          const { pubkeys: { voucher } } = await program.methods.initVoucherV0()
+            .accounts({
+              payer: me,
+              cronJob,
+              mint,
+              walletShare,
+              cronJobTransaction: cronJobTransactionKey(cronJob, id)[0],
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpcAndKeys()
+          vouchers.push(voucher)
+        }
+        [voucher1, voucher2] = vouchers
+      })
+
+      it("should have members absorb the fee and harvest withheld fees when closing", async () => {
+        // 990000 arrived, a quarter of it is owed to wallet1 and the fee comes out of its payout
+        await claimTo(wallet1, voucher1, walletShare1)
+        // @ts-ignore
+        expect(Number((await getAccount(provider.connection, ata(wallet1.publicKey), undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(245025);
+
+        for (const [walletShare, voucher, id] of [[walletShare1, voucher1, 0], [walletShare2, voucher2, 1]] as const) {
+          await program.methods.closeWalletShareV0()
+            .accounts({ walletShare })
+            .remainingAccounts(settleAccounts(walletShare))
+            .rpc()
+          await program.methods.closeVoucherV0()
+            .accounts({
+              cronJob,
+              voucher,
+              cronJobTransaction: cronJobTransactionKey(cronJob, id)[0],
+              tokenInflow: tokenInflowKey(fanout, mint)[0],
+              tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc()
+        }
+
+        // The fee withheld when the inflow arrived would otherwise keep the account from closing
+        await program.methods.closeTokenInflowV0()
+          .accounts({
+            tokenInflow: tokenInflowKey(fanout, mint)[0],
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .rpc()
+
+        expect(await provider.connection.getAccountInfo(fanoutAta)).to.be.null
+        expect(
+          await program.account.tokenInflowV0.fetchNullable(tokenInflowKey(fanout, mint)[0])
+        ).to.be.null
+        const mintAcc = await getMint(provider.connection, mint, undefined, TOKEN_2022_PROGRAM_ID)
+        expect(Number(getTransferFeeConfig(mintAcc)!.withheldAmount)).to.equal(10000);
+      })
+
+      it("should have the pool pay the fee out of what it retains and recover it from the next inflow", async () => {
+        await program.methods.updateFanoutV0({ transferFeePolicy: { pool: {} }, claimWindowSecs: null })
+          .accounts({ payer: me, fanout })
+          .rpc()
+
+        // Under Rebase all of the 990000 is owed to members, so there is nothing to cover the fee with
+        await claimTo(wallet1, voucher1, walletShare1)
+        // @ts-ignore
+        expect(Number((await getAccount(provider.connection, ata(wallet1.publicKey), undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(245025);
+
+        // Under Retain the unissued 60 of 100 shares of the next 990000 stay in the fanout
+        await program.methods.updateUnissuedSharePolicyV0({
+          unissuedSharePolicy: { retain: {} },
+        })
+          .accounts({ payer: me, fanout })
+          .remainingAccounts([
+            { pubkey: tokenInflowKey(fanout, mint)[0], isWritable: true, isSigner: false },
+            { pubkey: fanoutAta, isWritable: false, isSigner: false },
+          ])
+          .rpc()
+        await sendToFanout(1000000)
+
+        // wallet1 receives its full 99000, the pool sends 1000 on top to cover the fee
+        await claimTo(wallet1, voucher1, walletShare1)
+        // @ts-ignore
+        expect(Number((await getAccount(provider.connection, ata(wallet1.publicKey), undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(245025 + 99000);
+        let tokenInflowAcc = await program.account.tokenInflowV0.fetch(tokenInflowKey(fanout, mint)[0])
+        expect(tokenInflowAcc.unrecoveredFees.toNumber()).to.equal(1000);
+        expect(tokenInflowAcc.retainedAmount.toNumber()).to.equal(593000);
+
+        // The fee paid comes out of the next inflow before anyone is credited with it, so only
+        // 989000 of the 990000 that arrived is split
+        await sendToFanout(1000000)
+        await claimTo(wallet1, voucher1, walletShare1)
+        // @ts-ignore
+        expect(Number((await getAccount(provider.connection, ata(wallet1.publicKey), undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(245025 + 99000 + 98900);
+        tokenInflowAcc = await program.account.tokenInflowV0.fetch(tokenInflowKey(fanout, mint)[0])
+        expect(tokenInflowAcc.unrecoveredFees.toNumber()).to.equal(999);
+      })
+    })
+
     describe("with multiple wallets", () => {
       const newWallet1 = Keypair.generate()
       const newWallet2 = Keypair.generate()
@@ -229,6 +538,14 @@ describe("fanout", () => {
       let walletShare1: PublicKey;
       let walletShare2: PublicKey;
 
+      function settleAccounts(walletShare: PublicKey) {
+        return [
+          { pubkey: tokenInflowKey(fanout, mint)[0], isWritable: true, isSigner: false },
+          { pubkey: getAssociatedTokenAddressSync(mint, fanout, true), isWritable: false, isSigner: false },
+          { pubkey: voucherKey(fanout, mint, walletShare)[0], isWritable: true, isSigner: false },
+        ]
+      }
+
       beforeEach(async () => {
         mint = await createMint(provider, 8, me, me)
         await createAtaAndMint(provider, mint, 1000000000, fanout)
@@ -265,9 +582,11 @@ describe("fanout", () => {
         const { pubkeys: { voucher } } = await program.methods.initVoucherV0()
           .accounts({
             payer: me,
+            cronJob,
             mint,
             walletShare: walletShare1,
-            cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0]
+            cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0],
+            tokenProgram: TOKEN_PROGRAM_ID,
           })
           .rpcAndKeys()
 
@@ -287,17 +606,21 @@ describe("fanout", () => {
           const { pubkeys: { voucher: voucher1K } } = await program.methods.initVoucherV0()
             .accounts({
               payer: me,
+              cronJob,
               mint,
               walletShare: walletShare1,
-              cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0]
+              cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0],
+              tokenProgram: TOKEN_PROGRAM_ID,
             })
             .rpcAndKeys()
           const { pubkeys: { voucher: voucher2K } } = await program.methods.initVoucherV0()
             .accounts({
               payer: me,
+              cronJob,
               mint,
               walletShare: walletShare2,
-              cronJobTransaction: cronJobTransactionKey(cronJob, 1)[0]
+              cronJobTransaction: cronJobTransactionKey(cronJob, 1)[0],
+              tokenProgram: TOKEN_PROGRAM_ID,
             })
             .rpcAndKeys()
 
@@ -340,6 +663,21 @@ describe("fanout", () => {
           }
         }
 
+        it("should preview claims without claiming", async () => {
+          const preview = await program.methods.previewClaimV0()
+            .accounts({
+              fanout,
+              voucher: voucher1,
+              balanceAccount: getAssociatedTokenAddressSync(mint, fanout, true),
+            })
+            .view()
+
+          expect(preview.claimable.toNumber()).to.equal(250000000);
+          expect(preview.newInflow.toNumber()).to.equal(1000000000);
+          const tokenInflowAcc = await program.account.tokenInflowV0.fetch(tokenInflowKey(fanout, mint)[0])
+          expect(tokenInflowAcc.totalInflow.toNumber()).to.equal(0);
+        })
+
         it("should claim vouchers via tuktuk", async () => {
           // First run to trigger the cron job to trigger the tasks
           await runAllTasks()
@@ -378,6 +716,806 @@ describe("fanout", () => {
 
           expect(voucher1Acc.lastClaimedInflow.toString()).to.equal(tokenInflowAcc.totalInflow.toString());
           expect(voucher2Acc.lastClaimedInflow.toString()).to.equal(tokenInflowAcc.totalInflow.toString());
+          expect(voucher1Acc.totalClaimed.toNumber()).to.equal(250000000);
+          expect(voucher1Acc.claimCount).to.equal(1);
+          expect(voucher1Acc.lastClaimSlot.toNumber()).to.be.greaterThan(0);
+          expect(voucher1Acc.lastClaimUnixTs.toNumber()).to.be.greaterThan(0);
+        })
+
+        it("should hold payouts while paused and pay them once resumed", async () => {
+          const tokenInflow = tokenInflowKey(fanout, mint)[0]
+          await program.methods.pauseFanoutV0()
+            .accounts({ fanout, tokenInflow })
+            .rpc()
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const fanoutAta = getAssociatedTokenAddressSync(mint, fanout, true)
+          // @ts-ignore
+          expect(Number((await getAccount(provider.connection, fanoutAta)).amount)).to.equal(1000000000);
+          const preview = await program.methods.previewClaimV0()
+            .accounts({ fanout, voucher: voucher1, balanceAccount: fanoutAta })
+            .view()
+          expect(preview.paused).to.be.true;
+          expect(preview.claimable.toNumber()).to.equal(250000000);
+
+          await program.methods.resumeFanoutV0()
+            .accounts({ fanout, tokenInflow })
+            .rpc()
+          await program.methods.pauseFanoutV0()
+            .accounts({ fanout, tokenInflow: null })
+            .rpc()
+          const destination = await createAtaAndMint(provider, mint, 0, Keypair.generate().publicKey)
+          try {
+            await program.methods.claimToV0()
+              .accounts({
+                wallet: newWallet1.publicKey,
+                fanout,
+                mint,
+                voucher: voucher1,
+                walletShare: walletShare1,
+                destination,
+                tokenProgram: TOKEN_PROGRAM_ID,
+              })
+              .signers([newWallet1])
+              .rpc()
+            expect.fail("claim should fail while paused")
+          } catch (e: any) {
+            expect(e.toString()).to.include("FanoutPaused")
+          }
+
+          await program.methods.resumeFanoutV0()
+            .accounts({ fanout, tokenInflow: null })
+            .rpc()
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          // @ts-ignore
+          expect(Number((await getAccount(provider.connection, fanoutAta)).amount)).to.equal(0);
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          );
+          expect(Number(wallet1TokenAccount.amount)).to.equal(250000000);
+        })
+
+        it("should escrow a suspended member's payouts and release them once unsuspended", async () => {
+          await program.methods.suspendWalletShareV0()
+            .accounts({ fanout, walletShare: walletShare1 })
+            .rpc()
+
+          // Nothing is claimable while suspended, a claim would withhold it
+          const preview = await program.methods.previewClaimV0()
+            .accounts({
+              fanout,
+              voucher: voucher1,
+              balanceAccount: getAssociatedTokenAddressSync(mint, fanout, true),
+            })
+            .view()
+          expect(preview.claimable.toNumber()).to.equal(0);
+          expect(preview.escrowedAmount.toNumber()).to.equal(250000000);
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          let voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          expect(voucher1Acc.escrowedAmount.toNumber()).to.equal(250000000);
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(0);
+          const wallet2TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet2.publicKey)
+          );
+          expect(Number(wallet2TokenAccount.amount)).to.equal(750000000);
+
+          try {
+            await program.methods.closeWalletShareV0()
+              .accounts({ fanout, walletShare: walletShare1, rentRefund: me })
+              .remainingAccounts(settleAccounts(walletShare1))
+              .rpc()
+            expect.fail("close should fail while suspended")
+          } catch (e: any) {
+            expect(e.toString()).to.include("WalletShareSuspended")
+          }
+
+          await program.methods.unsuspendWalletShareV0({ forfeit: false })
+            .accounts({ fanout, walletShare: walletShare1 })
+            .remainingAccounts(settleAccounts(walletShare1))
+            .rpc()
+
+          voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          expect(voucher1Acc.escrowedAmount.toNumber()).to.equal(0);
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(250000000);
+          const walletShare1Acc = await program.account.walletShareV0.fetch(walletShare1);
+          expect(walletShare1Acc.suspended).to.be.false;
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          );
+          expect(Number(wallet1TokenAccount.amount)).to.equal(250000000);
+        })
+
+        it("should return a suspended member's escrow to the pool when forfeited", async () => {
+          await program.methods.suspendWalletShareV0()
+            .accounts({ fanout, walletShare: walletShare1 })
+            .rpc()
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          await program.methods.unsuspendWalletShareV0({ forfeit: true })
+            .accounts({ fanout, walletShare: walletShare1 })
+            .remainingAccounts(settleAccounts(walletShare1))
+            .rpc()
+
+          const voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          expect(voucher1Acc.escrowedAmount.toNumber()).to.equal(0);
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(0);
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          // The forfeited 250000000 is split 10:30 like new inflow
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          );
+          const wallet2TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet2.publicKey)
+          );
+          expect(Number(wallet1TokenAccount.amount)).to.equal(62500000);
+          expect(Number(wallet2TokenAccount.amount)).to.equal(937500000);
+        })
+
+        it("should skip payouts below the minimum payout", async () => {
+          const tokenInflow = tokenInflowKey(fanout, mint)[0]
+          await program.methods.updateTokenInflowV0({ minPayout: new anchor.BN(300000000) })
+            .accounts({ fanout, tokenInflow })
+            .rpc()
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const wallet1Ata = getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          const wallet2Ata = getAssociatedTokenAddressSync(mint, newWallet2.publicKey)
+          // @ts-ignore
+          expect(Number((await getAccount(provider.connection, wallet1Ata)).amount)).to.equal(0);
+          // @ts-ignore
+          expect(Number((await getAccount(provider.connection, wallet2Ata)).amount)).to.equal(750000000);
+          const voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          expect(voucher1Acc.lastClaimedInflow.toNumber()).to.equal(0);
+
+          // The member can only raise the threshold for themselves
+          await program.methods.updateVoucherV0({ minPayout: new anchor.BN(600000000) })
+            .accounts({ wallet: newWallet1.publicKey, voucher: voucher1 })
+            .signers([newWallet1])
+            .rpc()
+          await sendInstructions(provider, [
+            createMintToInstruction(mint, getAssociatedTokenAddressSync(mint, fanout, true), me, 1000000000),
+          ])
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          // @ts-ignore
+          expect(Number((await getAccount(provider.connection, wallet1Ata)).amount)).to.equal(0);
+          // @ts-ignore
+          expect(Number((await getAccount(provider.connection, wallet2Ata)).amount)).to.equal(1500000000);
+        })
+
+        it("should create missing receiver token accounts from the ata payer", async () => {
+          const wallet1Ata = getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          await sendInstructions(provider, [
+            createCloseAccountInstruction(wallet1Ata, me, newWallet1.publicKey),
+          ], [newWallet1])
+          await sendInstructions(provider, [SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: ataPayerKey(fanout)[0],
+            lamports: 100000000,
+          })]);
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            wallet1Ata
+          );
+          expect(Number(wallet1TokenAccount.amount)).to.equal(250000000);
+
+          // Closing it again doesn't get it recreated, with or without anything owed
+          await sendInstructions(provider, [
+            createBurnInstruction(wallet1Ata, mint, newWallet1.publicKey, 250000000),
+            createCloseAccountInstruction(wallet1Ata, me, newWallet1.publicKey),
+          ], [newWallet1])
+          const ataPayerBalance = await provider.connection.getBalance(ataPayerKey(fanout)[0])
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          expect(await provider.connection.getAccountInfo(wallet1Ata)).to.be.null
+          expect(await provider.connection.getBalance(ataPayerKey(fanout)[0])).to.equal(ataPayerBalance)
+
+          await sendInstructions(provider, [
+            createMintToInstruction(mint, getAssociatedTokenAddressSync(mint, fanout, true), me, 1000000000),
+          ])
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          expect(await provider.connection.getAccountInfo(wallet1Ata)).to.be.null
+          const voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(250000000);
+        })
+
+        it("should escrow the payout when the receiver token account can't be created", async () => {
+          const wallet1Ata = getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          await sendInstructions(provider, [
+            createCloseAccountInstruction(wallet1Ata, me, newWallet1.publicKey),
+          ], [newWallet1])
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          const tokenInflowAcc = await program.account.tokenInflowV0.fetch(
+            tokenInflowKey(fanout, mint)[0]
+          );
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(250000000);
+          expect(voucher1Acc.lastClaimedInflow.toString()).to.equal(tokenInflowAcc.totalInflow.toString());
+          const fanoutTokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, fanout, true)
+          );
+          expect(Number(fanoutTokenAccount.amount)).to.equal(250000000);
+        })
+
+        it("should let the authority forfeit pending amounts once the claim window elapses", async () => {
+          const wallet1Ata = getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          await sendInstructions(provider, [
+            createCloseAccountInstruction(wallet1Ata, me, newWallet1.publicKey),
+          ], [newWallet1])
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const fanoutAta = getAssociatedTokenAddressSync(mint, fanout, true)
+          const forfeit = () => program.methods.forfeitVoucherV0({ destination: { redistribute: {} } })
+            .accounts({ fanout, voucher: voucher1, walletShare: walletShare1, balanceAccount: fanoutAta })
+            .rpc()
+
+          await program.methods.updateFanoutV0({ transferFeePolicy: null, claimWindowSecs: new anchor.BN(3600) })
+            .accounts({ payer: me, fanout })
+            .rpc()
+          try {
+            await forfeit()
+            expect.fail("forfeit should fail before the window elapses")
+          } catch (e: any) {
+            expect(e.toString()).to.include("ClaimWindowNotElapsed")
+          }
+
+          await program.methods.updateFanoutV0({ transferFeePolicy: null, claimWindowSecs: new anchor.BN(1) })
+            .accounts({ payer: me, fanout })
+            .rpc()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await forfeit()
+
+          const voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(0);
+          // The forfeited 250000000 is split 10:30 like new inflow
+          const preview = await program.methods.previewClaimV0()
+            .accounts({ fanout, voucher: voucher2, balanceAccount: fanoutAta })
+            .view()
+          expect(preview.claimable.toNumber()).to.equal(187500000);
+        })
+
+        it("should escrow payouts to frozen accounts and let the member withdraw them", async () => {
+          const wallet1Ata = getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          await sendInstructions(provider, [
+            createFreezeAccountInstruction(wallet1Ata, mint, me),
+          ])
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(250000000);
+
+          const destinationOwner = Keypair.generate()
+          const destination = await createAtaAndMint(provider, mint, 0, destinationOwner.publicKey)
+          await program.methods.withdrawPendingV0()
+            .accounts({
+              wallet: newWallet1.publicKey,
+              fanout,
+              mint,
+              voucher: voucher1,
+              destination,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              walletShare: walletShare1,
+            })
+            .signers([newWallet1])
+            .rpc()
+
+          const destinationAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            destination
+          );
+          expect(Number(destinationAccount.amount)).to.equal(250000000);
+          expect((await program.account.voucherV0.fetch(voucher1)).pendingAmount.toNumber()).to.equal(0);
+        })
+
+        it("should allow members to claim to any token account", async () => {
+          const destinationOwner = Keypair.generate()
+          const destination = await createAtaAndMint(provider, mint, 0, destinationOwner.publicKey)
+          await program.methods.claimToV0()
+            .accounts({
+              wallet: newWallet1.publicKey,
+              fanout,
+              mint,
+              voucher: voucher1,
+              walletShare: walletShare1,
+              destination,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([newWallet1])
+            .rpc()
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const destinationAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            destination
+          );
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          );
+          expect(Number(destinationAccount.amount)).to.equal(250000000);
+          expect(Number(wallet1TokenAccount.amount)).to.equal(0);
+        })
+
+        it("should settle inflows at the old total shares when changing them", async () => {
+          await program.methods.updateTotalSharesV0({
+            totalShares: 200,
+          })
+            .accounts({
+              fanout,
+            })
+            .remainingAccounts([
+              { pubkey: tokenInflowKey(fanout, mint)[0], isWritable: true, isSigner: false },
+              { pubkey: getAssociatedTokenAddressSync(mint, fanout, true), isWritable: false, isSigner: false },
+            ])
+            .rpc()
+
+          const fanoutAcc = await program.account.fanoutV0.fetch(fanout)
+          expect(fanoutAcc.totalShares).to.equal(200)
+          const tokenInflowAcc = await program.account.tokenInflowV0.fetch(tokenInflowKey(fanout, mint)[0])
+          expect(tokenInflowAcc.totalShares).to.equal(100)
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          );
+          const wallet2TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet2.publicKey)
+          );
+          expect(Number(wallet1TokenAccount.amount)).to.equal(250000000);
+          expect(Number(wallet2TokenAccount.amount)).to.equal(750000000);
+        })
+
+        it("should settle vouchers at their old share count when shares change", async () => {
+          await program.methods.updateWalletShareV0({
+            shares: 30,
+            index: 0,
+          })
+            .accounts({
+              payer: me,
+              fanout,
+              wallet: newWallet1.publicKey,
+            })
+            .remainingAccounts(settleAccounts(walletShare1))
+            .rpc()
+
+          const voucher1Acc = await program.account.voucherV0.fetch(voucher1)
+          expect(voucher1Acc.pendingAmount.toNumber()).to.equal(250000000)
+          expect(voucher1Acc.shares).to.equal(30)
+
+          await sendInstructions(provider, [
+            createMintToInstruction(
+              mint,
+              getAssociatedTokenAddressSync(mint, fanout, true),
+              me,
+              1200000000
+            ),
+          ])
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          );
+          const wallet2TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet2.publicKey)
+          );
+          expect(Number(wallet1TokenAccount.amount)).to.equal(850000000);
+          expect(Number(wallet2TokenAccount.amount)).to.equal(1350000000);
+        })
+
+        it("should route the unissued portion to the treasury", async () => {
+          const treasury = Keypair.generate()
+          await createAtaAndMint(provider, mint, 0, treasury.publicKey)
+          await program.methods.updateUnissuedSharePolicyV0({
+            unissuedSharePolicy: { treasury: { 0: treasury.publicKey } },
+          })
+            .accounts({
+              payer: me,
+              fanout,
+            })
+            .remainingAccounts([
+              { pubkey: tokenInflowKey(fanout, mint)[0], isWritable: true, isSigner: false },
+              { pubkey: getAssociatedTokenAddressSync(mint, fanout, true), isWritable: false, isSigner: false },
+            ])
+            .rpc()
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          await program.methods.claimTreasuryV0()
+            .accounts({
+              fanout,
+              mint,
+              treasury: treasury.publicKey,
+              treasuryTokenAccount: getAssociatedTokenAddressSync(mint, treasury.publicKey),
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc()
+
+          const wallet1TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+          );
+          const wallet2TokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, newWallet2.publicKey)
+          );
+          const treasuryTokenAccount = await getAccount(
+            // @ts-ignore
+            provider.connection,
+            getAssociatedTokenAddressSync(mint, treasury.publicKey)
+          );
+          expect(Number(wallet1TokenAccount.amount)).to.equal(100000000);
+          expect(Number(wallet2TokenAccount.amount)).to.equal(300000000);
+          expect(Number(treasuryTokenAccount.amount)).to.equal(600000000);
+        })
+
+        it("should pay what a previous treasury was owed to the authority under Retain", async () => {
+          const treasury = Keypair.generate()
+          const setPolicy = (unissuedSharePolicy: any) => program.methods.updateUnissuedSharePolicyV0({
+            unissuedSharePolicy,
+          })
+            .accounts({
+              payer: me,
+              fanout,
+            })
+            .remainingAccounts([
+              { pubkey: tokenInflowKey(fanout, mint)[0], isWritable: true, isSigner: false },
+              { pubkey: getAssociatedTokenAddressSync(mint, fanout, true), isWritable: false, isSigner: false },
+            ])
+            .rpc()
+          await setPolicy({ treasury: { 0: treasury.publicKey } })
+          await setPolicy({ retain: {} })
+
+          const tokenInflowAcc = await program.account.tokenInflowV0.fetch(tokenInflowKey(fanout, mint)[0])
+          expect(tokenInflowAcc.treasuryAmount.toNumber()).to.equal(600000000)
+
+          const authorityAta = getAssociatedTokenAddressSync(mint, me)
+          await sendInstructions(provider, [
+            createAssociatedTokenAccountIdempotentInstruction(me, authorityAta, me, mint),
+          ])
+          await program.methods.claimTreasuryV0()
+            .accounts({
+              fanout,
+              mint,
+              treasury: me,
+              treasuryTokenAccount: authorityAta,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc()
+
+          // @ts-ignore
+          expect(Number((await getAccount(provider.connection, authorityAta)).amount)).to.equal(600000000);
+        })
+
+        it("should claim SOL vouchers via tuktuk", async () => {
+          await program.methods.initializeSolVoucherV0()
+            .accounts({
+              payer: me,
+              cronJob,
+              walletShare: walletShare1,
+              cronJobTransaction: cronJobTransactionKey(cronJob, 2)[0]
+            })
+            .rpc()
+          await program.methods.initializeSolVoucherV0()
+            .accounts({
+              payer: me,
+              cronJob,
+              walletShare: walletShare2,
+              cronJobTransaction: cronJobTransactionKey(cronJob, 3)[0]
+            })
+            .rpc()
+          await sendInstructions(provider, [SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: fanout,
+            lamports: 1000000000,
+          })]);
+
+          await runAllTasks()
+          await new Promise(resolve => setTimeout(resolve, 2000))
+          await runAllTasks()
+
+          expect(await provider.connection.getBalance(newWallet1.publicKey)).to.equal(250000000);
+          expect(await provider.connection.getBalance(newWallet2.publicKey)).to.equal(750000000);
+        })
+
+        it("should escrow SOL payouts that would leave the wallet below rent", async () => {
+          const emptyWallet = Keypair.generate()
+          const { pubkeys: { walletShare } } = await program.methods.updateWalletShareV0({
+            shares: 10,
+            index: 2,
+          })
+            .accounts({
+              payer: me,
+              fanout,
+              wallet: emptyWallet.publicKey,
+            })
+            .rpcAndKeys()
+          const { pubkeys: { voucher } } = await program.methods.initializeSolVoucherV0()
+            .accounts({
+              payer: me,
+              cronJob,
+              walletShare,
+              cronJobTransaction: cronJobTransactionKey(cronJob, 2)[0]
+            })
+            .rpcAndKeys()
+          const claim = () => program.methods.claimSolV0()
+            .accounts({
+              fanout,
+              voucher,
+              walletShare,
+              wallet: emptyWallet.publicKey,
+            })
+            .rpc()
+
+          // A fifth of 1000 lamports can't fund an empty wallet, so it stays in the fanout
+          await sendInstructions(provider, [SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: fanout,
+            lamports: 1000,
+          })]);
+          const preview = await program.methods.previewClaimV0()
+            .accounts({ fanout, voucher, balanceAccount: fanout })
+            .view()
+          expect(preview.claimable.toNumber()).to.equal(0);
+          expect(preview.pendingAmount.toNumber()).to.equal(200);
+          await claim()
+          expect(await provider.connection.getBalance(emptyWallet.publicKey)).to.equal(0);
+          let voucherAcc = await program.account.voucherV0.fetch(voucher)
+          expect(voucherAcc.pendingAmount.toNumber()).to.equal(200);
+
+          await sendInstructions(provider, [SystemProgram.transfer({
+            fromPubkey: me,
+            toPubkey: fanout,
+            lamports: 1000000000,
+          })]);
+          await claim()
+          expect(await provider.connection.getBalance(emptyWallet.publicKey)).to.equal(200000200);
+          voucherAcc = await program.account.voucherV0.fetch(voucher)
+          expect(voucherAcc.pendingAmount.toNumber()).to.equal(0);
+        })
+
+        it("should move vouchers to a new cron job when the schedule changes", async () => {
+          await program.methods.updateFanoutScheduleV0({
+            schedule: "0 0 * * * *",
+          })
+            .accounts({
+              payer: me,
+              fanout,
+            })
+            .rpc()
+
+          for (const [voucher, id] of [[voucher1, 0], [voucher2, 1]] as const) {
+            await program.methods.unscheduleVoucherV0()
+              .accounts({
+                fanout,
+                voucher,
+                cronJobTransaction: cronJobTransactionKey(cronJob, id)[0],
+                rentRefund: me,
+              })
+              .rpc()
+          }
+
+          const userCronJobsK = userCronJobsKey(queueAuthority)[0]
+          const userCronJobs = await cronProgram.account.userCronJobsV0.fetch(userCronJobsK)
+          const newCronJob = cronJobKey(queueAuthority, userCronJobs.nextCronJobId)[0]
+          const taskQueueAcc = await tuktukProgram.account.taskQueueV0.fetch(taskQueue)
+          const nextTask = nextAvailableTaskIds(taskQueueAcc.taskBitmap, 1, false)[0]
+          const returnAccounts = (job: PublicKey) => [1, 2].map((i) =>
+            PublicKey.findProgramAddressSync([Buffer.from(`task_return_account_${i}`), job.toBuffer()], CRON_PROGRAM_ID)[0]
+          )
+          const [oldTaskReturnAccount1, oldTaskReturnAccount2] = returnAccounts(cronJob)
+          const [taskReturnAccount1, taskReturnAccount2] = returnAccounts(newCronJob)
+          const oldCronJobBalance = await provider.connection.getBalance(cronJob)
+          await program.methods.migrateFanoutCronJobV0()
+            .preInstructions([
+              ComputeBudgetProgram.setComputeUnitLimit({ units: 1000000 })
+            ])
+            .accounts({
+              payer: me,
+              authority: me,
+              fanout,
+              cronJob,
+              oldTaskReturnAccount1,
+              oldTaskReturnAccount2,
+              taskQueue,
+              userCronJobs: userCronJobsK,
+              newCronJob,
+              cronJobNameMapping: cronJobNameMappingKey(queueAuthority, fanoutName)[0],
+              task: taskKey(taskQueue, nextTask)[0],
+              taskReturnAccount1,
+              taskReturnAccount2,
+            })
+            .rpc()
+
+          for (const [voucher, id] of [[voucher1, 0], [voucher2, 1]] as const) {
+            await program.methods.rescheduleVoucherV0()
+              .accounts({
+                payer: me,
+                fanout,
+                cronJob: newCronJob,
+                voucher,
+                mint,
+                cronJobTransaction: cronJobTransactionKey(newCronJob, id)[0],
+                tokenProgram: TOKEN_PROGRAM_ID,
+              })
+              .rpc()
+          }
+
+          const fanoutAcc = await program.account.fanoutV0.fetch(fanout)
+          expect(fanoutAcc.cronJob.toBase58()).to.equal(newCronJob.toBase58())
+          expect(fanoutAcc.pendingSchedule).to.be.null
+          const newCronJobAcc = await cronProgram.account.cronJobV0.fetch(newCronJob)
+          expect(newCronJobAcc.schedule).to.equal("0 0 * * * *")
+          expect(newCronJobAcc.numTransactions).to.equal(2)
+          // The old cron job's task funding moved over with it
+          expect(await provider.connection.getBalance(newCronJob)).to.be.greaterThan(1000000000)
+          expect(oldCronJobBalance).to.be.greaterThan(1000000000)
+          expect((await program.account.voucherV0.fetch(voucher1)).cronTransactionId).to.equal(0)
+          expect((await program.account.voucherV0.fetch(voucher2)).cronTransactionId).to.equal(1)
+        })
+
+        it("should run an inflow's vouchers on its own cron job", async () => {
+          const userCronJobsK = userCronJobsKey(queueAuthority)[0]
+          const userCronJobs = await cronProgram.account.userCronJobsV0.fetch(userCronJobsK)
+          const inflowCronJob = cronJobKey(queueAuthority, userCronJobs.nextCronJobId)[0]
+          const inflowCronJobName = `${fanoutName}/${mint.toBase58()}`
+          const taskQueueAcc = await tuktukProgram.account.taskQueueV0.fetch(taskQueue)
+          const nextTask = nextAvailableTaskIds(taskQueueAcc.taskBitmap, 1, false)[0]
+          const [taskReturnAccount1, taskReturnAccount2] = [1, 2].map((i) =>
+            PublicKey.findProgramAddressSync([Buffer.from(`task_return_account_${i}`), inflowCronJob.toBuffer()], CRON_PROGRAM_ID)[0]
+          )
+          await program.methods.initializeTokenInflowCronJobV0({
+            schedule: "0 0 0 * * *",
+          })
+            .preInstructions([
+              ComputeBudgetProgram.setComputeUnitLimit({ units: 1000000 })
+            ])
+            .accounts({
+              payer: me,
+              authority: me,
+              fanout,
+              tokenInflow: tokenInflowKey(fanout, mint)[0],
+              taskQueue,
+              userCronJobs: userCronJobsK,
+              cronJob: inflowCronJob,
+              cronJobNameMapping: cronJobNameMappingKey(queueAuthority, inflowCronJobName)[0],
+              task: taskKey(taskQueue, nextTask)[0],
+              taskReturnAccount1,
+              taskReturnAccount2,
+            })
+            .rpc()
+
+          const tokenInflowAcc = await program.account.tokenInflowV0.fetch(tokenInflowKey(fanout, mint)[0])
+          expect(tokenInflowAcc.cronJob!.toBase58()).to.equal(inflowCronJob.toBase58())
+          const inflowCronJobAcc = await cronProgram.account.cronJobV0.fetch(inflowCronJob)
+          expect(inflowCronJobAcc.name).to.equal(inflowCronJobName)
+          expect(inflowCronJobAcc.schedule).to.equal("0 0 0 * * *")
+
+          // Vouchers created before stay on the fanout's cron job until moved, which works while paused
+          await program.methods.pauseFanoutV0()
+            .accounts({ fanout, tokenInflow: null })
+            .rpc()
+          await program.methods.unscheduleVoucherV0()
+            .accounts({
+              fanout,
+              cronJob,
+              voucher: voucher1,
+              cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0],
+              rentRefund: me,
+            })
+            .rpc()
+          const reschedule = () => program.methods.rescheduleVoucherV0()
+            .accounts({
+              payer: me,
+              fanout,
+              cronJob: inflowCronJob,
+              voucher: voucher1,
+              mint,
+              cronJobTransaction: cronJobTransactionKey(inflowCronJob, 0)[0],
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc()
+          await reschedule()
+          try {
+            await reschedule()
+            expect.fail("a scheduled voucher should not be rescheduled")
+          } catch (e: any) {
+            expect(e.toString()).to.include("VoucherScheduled")
+          }
+          await program.methods.resumeFanoutV0()
+            .accounts({ fanout, tokenInflow: null })
+            .rpc()
+
+          expect((await cronProgram.account.cronJobV0.fetch(inflowCronJob)).numTransactions).to.equal(1)
+          expect((await cronProgram.account.cronJobV0.fetch(cronJob)).numTransactions).to.equal(1)
+          expect(
+            await cronProgram.account.cronJobTransactionV0.fetchNullable(cronJobTransactionKey(cronJob, 0)[0])
+          ).to.be.null
         })
 
         describe("with claimed rewards", () => {
@@ -387,18 +1525,158 @@ describe("fanout", () => {
             await runAllTasks()
           })
 
+          it("should settle the old wallet and rewrite the cron claim when rotating", async () => {
+            const rotatedWallet = Keypair.generate()
+            await createAtaAndMint(provider, mint, 0, rotatedWallet.publicKey)
+            await program.methods.updateWalletShareV0({
+              shares: 10,
+              index: 0,
+            })
+              .accounts({
+                payer: me,
+                fanout,
+                wallet: rotatedWallet.publicKey,
+              })
+              .remainingAccounts(settleAccounts(walletShare1))
+              .rpc()
+
+            // New inflow that arrived while the old wallet was still on the voucher
+            await sendInstructions(provider, [
+              createMintToInstruction(
+                mint,
+                getAssociatedTokenAddressSync(mint, fanout, true),
+                me,
+                1000000000
+              ),
+            ])
+
+            const rotate = () => program.methods.rotateVoucherWalletV0()
+              .accounts({
+                payer: me,
+                cronJob,
+                voucher: voucher1,
+                walletShare: walletShare1,
+                wallet: newWallet1.publicKey,
+                mint,
+                cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0],
+                tokenProgram: TOKEN_PROGRAM_ID,
+              })
+              .rpc()
+
+            // What the old wallet accrued can't be handed to the new one while payouts are paused
+            await program.methods.pauseFanoutV0()
+              .accounts({ fanout, tokenInflow: null })
+              .rpc()
+            try {
+              await rotate()
+              expect.fail("rotation should fail while paused")
+            } catch (e: any) {
+              expect(e.toString()).to.include("FanoutPaused")
+            }
+            await program.methods.resumeFanoutV0()
+              .accounts({ fanout, tokenInflow: null })
+              .rpc()
+
+            await rotate()
+
+            const oldWalletTokenAccount = await getAccount(
+              // @ts-ignore
+              provider.connection,
+              getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+            );
+            expect(Number(oldWalletTokenAccount.amount)).to.equal(500000000);
+
+            const voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+            expect(voucher1Acc.wallet.toBase58()).to.equal(rotatedWallet.publicKey.toBase58());
+
+            const cronJobTransactionAcc = await cronProgram.account.cronJobTransactionV0.fetch(
+              cronJobTransactionKey(cronJob, 0)[0]
+            );
+            const accounts = cronJobTransactionAcc.transaction.compiledV0![0].accounts.map((a) => a.toBase58());
+            expect(accounts).to.include(rotatedWallet.publicKey.toBase58());
+            expect(accounts).to.include(
+              getAssociatedTokenAddressSync(mint, rotatedWallet.publicKey).toBase58()
+            );
+            expect(accounts).to.not.include(newWallet1.publicKey.toBase58());
+          })
+
+          it("should carry what a lost wallet can't receive over to the new wallet", async () => {
+            const rotatedWallet = Keypair.generate()
+            await createAtaAndMint(provider, mint, 0, rotatedWallet.publicKey)
+            await program.methods.updateWalletShareV0({
+              shares: 10,
+              index: 0,
+            })
+              .accounts({
+                payer: me,
+                fanout,
+                wallet: rotatedWallet.publicKey,
+              })
+              .remainingAccounts(settleAccounts(walletShare1))
+              .rpc()
+            await sendInstructions(provider, [
+              createMintToInstruction(
+                mint,
+                getAssociatedTokenAddressSync(mint, fanout, true),
+                me,
+                1000000000
+              ),
+            ])
+
+            // The old wallet's token account is gone, so nothing can be paid to it
+            const oldWalletAta = getAssociatedTokenAddressSync(mint, newWallet1.publicKey)
+            await sendInstructions(provider, [
+              createBurnInstruction(oldWalletAta, mint, newWallet1.publicKey, 250000000),
+              createCloseAccountInstruction(oldWalletAta, me, newWallet1.publicKey),
+            ], [newWallet1])
+
+            await program.methods.rotateVoucherWalletV0()
+              .accounts({
+                payer: me,
+                cronJob,
+                voucher: voucher1,
+                walletShare: walletShare1,
+                wallet: newWallet1.publicKey,
+                mint,
+                cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0],
+                tokenProgram: TOKEN_PROGRAM_ID,
+              })
+              .rpc()
+
+            let voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+            expect(voucher1Acc.wallet.toBase58()).to.equal(rotatedWallet.publicKey.toBase58());
+            expect(voucher1Acc.pendingAmount.toNumber()).to.equal(250000000);
+
+            // The next cron claim pays it to the new wallet
+            await runAllTasks()
+            await new Promise(resolve => setTimeout(resolve, 2000))
+            await runAllTasks()
+
+            const rotatedWalletTokenAccount = await getAccount(
+              // @ts-ignore
+              provider.connection,
+              getAssociatedTokenAddressSync(mint, rotatedWallet.publicKey)
+            );
+            expect(Number(rotatedWalletTokenAccount.amount)).to.equal(250000000);
+            voucher1Acc = await program.account.voucherV0.fetch(voucher1);
+            expect(voucher1Acc.pendingAmount.toNumber()).to.equal(0);
+          })
+
           it("should allow closing wallet shares, vouchers, inflow, then fanout", async () => {
             await program.methods.closeWalletShareV0()
               .accounts({
                 walletShare: walletShare1,
               })
+              .remainingAccounts(settleAccounts(walletShare1))
               .rpc()
 
             await program.methods.closeVoucherV0()
               .accounts({
+                cronJob,
                 voucher: voucher1,
                 cronJobTransaction: cronJobTransactionKey(cronJob, 0)[0],
                 tokenInflow: tokenInflowKey(fanout, mint)[0],
+                tokenProgram: TOKEN_PROGRAM_ID,
               })
               .rpc()
 
@@ -406,19 +1684,23 @@ describe("fanout", () => {
               .accounts({
                 walletShare: walletShare2,
               })
+              .remainingAccounts(settleAccounts(walletShare2))
               .rpc()
 
             await program.methods.closeVoucherV0()
               .accounts({
+                cronJob,
                 voucher: voucher2,
                 cronJobTransaction: cronJobTransactionKey(cronJob, 1)[0],
                 tokenInflow: tokenInflowKey(fanout, mint)[0],
+                tokenProgram: TOKEN_PROGRAM_ID,
               })
               .rpc()
 
             await program.methods.closeTokenInflowV0()
               .accounts({
                 tokenInflow: tokenInflowKey(fanout, mint)[0],
+                tokenProgram: TOKEN_PROGRAM_ID,
               })
               .rpc()
 